@@ -0,0 +1,18 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use bigdecimal::BigDecimal;
+use std::str::FromStr;
+
+/// Parses decimal text, e.g. `"12345678901234567890.123456789"`.
+pub fn big_decimal_from_text(text: &str, field: &str) -> TursoMapperResult<BigDecimal> {
+    BigDecimal::from_str(text).map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a decimal: {:?}", field, text)))
+}
+
+/// Converts an integer exactly.
+pub fn big_decimal_from_integer(integer: i64) -> BigDecimal {
+    BigDecimal::from(integer)
+}
+
+/// Converts a real, failing for NaN and infinities.
+pub fn big_decimal_from_real(real: f64, field: &str) -> TursoMapperResult<BigDecimal> {
+    BigDecimal::try_from(real).map_err(|_| TursoMapperError::ConversionError(format!("{} value {} cannot be represented as a decimal", field, real)))
+}
@@ -0,0 +1,43 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+// The format SQLite's own date and time functions produce, with optional fractional seconds
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Parses RFC3339 text, or SQLite's `YYYY-MM-DD HH:MM:SS` format interpreted as UTC.
+pub fn datetime_utc_from_text(text: &str, field: &str) -> TursoMapperResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(text, SQLITE_DATETIME_FORMAT).map(|datetime| datetime.and_utc()))
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a date and time: {:?}", field, text)))
+}
+
+/// Interprets an integer as seconds since the unix epoch.
+pub fn datetime_utc_from_unix(seconds: i64, field: &str) -> TursoMapperResult<DateTime<Utc>> {
+    DateTime::from_timestamp(seconds, 0)
+        .ok_or_else(|| TursoMapperError::ConversionError(format!("{} value {} is out of range for a unix timestamp", field, seconds)))
+}
+
+/// Parses SQLite's `YYYY-MM-DD HH:MM:SS` format, or RFC3339 text converted to UTC.
+pub fn naive_datetime_from_text(text: &str, field: &str) -> TursoMapperResult<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text, SQLITE_DATETIME_FORMAT)
+        .or_else(|_| DateTime::parse_from_rfc3339(text).map(|datetime| datetime.naive_utc()))
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a date and time: {:?}", field, text)))
+}
+
+/// Interprets an integer as seconds since the unix epoch, in UTC.
+pub fn naive_datetime_from_unix(seconds: i64, field: &str) -> TursoMapperResult<NaiveDateTime> {
+    datetime_utc_from_unix(seconds, field).map(|datetime| datetime.naive_utc())
+}
+
+/// Parses ISO `YYYY-MM-DD` text.
+pub fn naive_date_from_text(text: &str, field: &str) -> TursoMapperResult<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a date: {:?}", field, text)))
+}
+
+/// Parses `HH:MM`, `HH:MM:SS` or `HH:MM:SS.fff` text.
+pub fn naive_time_from_text(text: &str, field: &str) -> TursoMapperResult<NaiveTime> {
+    NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a time: {:?}", field, text)))
+}
@@ -0,0 +1,19 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use jiff::{Timestamp, Zoned};
+
+/// Parses RFC3339 or Temporal text.
+pub fn timestamp_from_text(text: &str, field: &str) -> TursoMapperResult<Timestamp> {
+    text.parse()
+        .map_err(|err| TursoMapperError::ConversionError(format!("{} could not be parsed as a timestamp: {:?} ({})", field, text, err)))
+}
+
+/// Interprets an integer as seconds since the unix epoch.
+pub fn timestamp_from_unix(seconds: i64, field: &str) -> TursoMapperResult<Timestamp> {
+    Timestamp::from_second(seconds).map_err(|_| TursoMapperError::ConversionError(format!("{} value {} is out of range for a unix timestamp", field, seconds)))
+}
+
+/// Parses Temporal text with a time zone annotation, e.g. `2024-03-01T10:30:45-05:00[America/New_York]`.
+pub fn zoned_from_text(text: &str, field: &str) -> TursoMapperResult<Zoned> {
+    text.parse()
+        .map_err(|err| TursoMapperError::ConversionError(format!("{} could not be parsed as a zoned date and time: {:?} ({})", field, text, err)))
+}
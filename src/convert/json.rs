@@ -0,0 +1,12 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use serde::de::DeserializeOwned;
+
+/// Deserializes JSON text into a `serde_json::Value` or any other deserializable type.
+pub fn json_from_text<T: DeserializeOwned>(text: &str, field: &str) -> TursoMapperResult<T> {
+    serde_json::from_str(text).map_err(|err| TursoMapperError::ConversionError(format!("{} could not be parsed as JSON: {}", field, err)))
+}
+
+/// Deserializes a blob holding UTF-8 encoded JSON into a `serde_json::Value` or any other deserializable type.
+pub fn json_from_blob<T: DeserializeOwned>(blob: &[u8], field: &str) -> TursoMapperResult<T> {
+    serde_json::from_slice(blob).map_err(|err| TursoMapperError::ConversionError(format!("{} could not be parsed as JSON: {}", field, err)))
+}
@@ -0,0 +1,60 @@
+//! Conversion helpers called by the code generated by the derive macros, mostly for types from optional dependencies.
+
+#[cfg(feature = "bigdecimal")]
+pub mod bigdecimal;
+
+#[cfg(feature = "chrono")]
+pub mod chrono;
+
+#[cfg(feature = "jiff")]
+pub mod jiff;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+pub mod numeric;
+
+#[cfg(feature = "semver")]
+pub mod semver;
+
+#[cfg(feature = "time")]
+pub mod time;
+
+#[cfg(feature = "uuid")]
+pub mod uuid;
+
+/// Names the storage class of a value for error messages, e.g. "an integer".
+pub fn storage_class(value: &turso::Value) -> &'static str {
+    match value {
+        turso::Value::Null => "NULL",
+        turso::Value::Integer(_) => "an integer",
+        turso::Value::Real(_) => "a real",
+        turso::Value::Text(_) => "a string",
+        turso::Value::Blob(_) => "a blob",
+    }
+}
+
+/// Reads the value at `index`, reporting a row with too few columns as a `ColumnNotFound` error instead of panicking.
+pub fn get_value(row: &turso::Row, index: usize) -> crate::TursoMapperResult<turso::Value> {
+    if index >= row.column_count() {
+        return Err(crate::TursoMapperError::ColumnNotFound(format!(
+            "index {} of a row with {} columns",
+            index,
+            row.column_count()
+        )));
+    }
+    Ok(row.get_value(index)?)
+}
+
+/// The largest of the column counts of the variants of a tagged enum, for its `COLUMN_COUNT`.
+pub const fn max_column_count(counts: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < counts.len() {
+        if counts[i] > max {
+            max = counts[i];
+        }
+        i += 1;
+    }
+    max
+}
@@ -0,0 +1,51 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use std::fmt::Display;
+use std::str::FromStr;
+
+// 2^63, the first f64 above the i64 range. i64::MAX rounds up to it, so it is excluded explicitly.
+const I64_UPPER_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+/// Converts an integer into an `f64` for a float field, failing if it has no exact `f64` equivalent.
+pub fn real_from_integer(value: i64, field: &str) -> TursoMapperResult<f64> {
+    let real = value as f64;
+    if real < I64_UPPER_BOUND && real as i64 == value {
+        Ok(real)
+    } else {
+        Err(lossy_conversion(field, value, "a real"))
+    }
+}
+
+/// Converts an integer into an `f32` for a float field, failing if it has no exact `f32` equivalent.
+pub fn f32_from_integer(value: i64, field: &str) -> TursoMapperResult<f32> {
+    let real = value as f32;
+    if (real as f64) < I64_UPPER_BOUND && real as i64 == value {
+        Ok(real)
+    } else {
+        Err(lossy_conversion(field, value, "an f32"))
+    }
+}
+
+/// Converts a real into an `i64` for an integer field, failing unless it is a whole number
+/// in the `i64` range.
+pub fn integer_from_real(value: f64, field: &str) -> TursoMapperResult<i64> {
+    if value.fract() == 0.0 && (-I64_UPPER_BOUND..I64_UPPER_BOUND).contains(&value) {
+        Ok(value as i64)
+    } else {
+        Err(lossy_conversion(field, value, "an integer"))
+    }
+}
+
+/// Parses a TEXT value for a numeric field marked `#[turso(parse_text)]`, reporting the text when it is not a number.
+pub fn parse_text<T>(value: &str, field: &str) -> TursoMapperResult<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| TursoMapperError::ConversionError(format!("{} value {:?} is not a valid number: {}", field, value, err)))
+}
+
+fn lossy_conversion(field: &str, value: impl Display, target: &str) -> TursoMapperError {
+    TursoMapperError::ConversionError(format!("{} value {} cannot be converted to {} without losing precision", field, value, target))
+}
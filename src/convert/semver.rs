@@ -0,0 +1,7 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use semver::Version;
+
+/// Parses a semantic version stored as text, e.g. `"1.2.3-beta.1"`.
+pub fn version_from_text(text: &str, field: &str) -> TursoMapperResult<Version> {
+    Version::parse(text).map_err(|err| TursoMapperError::ConversionError(format!("{} could not be parsed as a version: {:?} ({})", field, text, err)))
+}
@@ -0,0 +1,27 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use time::format_description::BorrowedFormatItem;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+// The format SQLite's own date and time functions produce, with optional fractional seconds
+const SQLITE_DATETIME_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]");
+
+/// Parses RFC3339 text, or SQLite's `YYYY-MM-DD HH:MM:SS` format interpreted as UTC.
+pub fn offset_datetime_from_text(text: &str, field: &str) -> TursoMapperResult<OffsetDateTime> {
+    OffsetDateTime::parse(text, &Rfc3339)
+        .or_else(|_| PrimitiveDateTime::parse(text, SQLITE_DATETIME_FORMAT).map(PrimitiveDateTime::assume_utc))
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a date and time: {:?}", field, text)))
+}
+
+/// Interprets an integer as seconds since the unix epoch.
+pub fn offset_datetime_from_unix(seconds: i64, field: &str) -> TursoMapperResult<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(seconds)
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} value {} is out of range for a unix timestamp", field, seconds)))
+}
+
+/// Parses SQLite's `YYYY-MM-DD HH:MM:SS` format.
+pub fn primitive_datetime_from_text(text: &str, field: &str) -> TursoMapperResult<PrimitiveDateTime> {
+    PrimitiveDateTime::parse(text, SQLITE_DATETIME_FORMAT)
+        .map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a date and time: {:?}", field, text)))
+}
@@ -0,0 +1,12 @@
+use crate::{TursoMapperError, TursoMapperResult};
+use uuid::Uuid;
+
+/// Reads a UUID stored as exactly 16 bytes.
+pub fn uuid_from_blob(blob: &[u8], field: &str) -> TursoMapperResult<Uuid> {
+    Uuid::from_slice(blob).map_err(|_| TursoMapperError::ConversionError(format!("{} must be 16 bytes to map to a uuid, got {}", field, blob.len())))
+}
+
+/// Parses a UUID stored as text, e.g. in the hyphenated form.
+pub fn uuid_from_text(text: &str, field: &str) -> TursoMapperResult<Uuid> {
+    Uuid::parse_str(text).map_err(|_| TursoMapperError::ConversionError(format!("{} could not be parsed as a uuid: {:?}", field, text)))
+}
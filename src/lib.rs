@@ -1,9 +1,25 @@
 #![allow(clippy::uninlined_format_args)]
 
+#[cfg(feature = "stream")]
+use futures_core::Stream;
+#[cfg(feature = "stream")]
+use futures_util::TryStreamExt;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt::Debug;
 use std::future::Future;
+use std::hash::Hash;
 use turso::{Column, Connection, IntoParams};
-pub use turso_mappers_derive::TryFromRowByIndex;
+// The generated code reaches turso through this crate, so it does not depend on how turso is named downstream
+#[doc(hidden)]
+pub use turso;
+pub use turso_mappers_derive::{Insert, ToParams, TryFromRow, TryFromRowByIndex, TryFromRowByName, TryFromValue, Update};
+
+// Lets the derives be used in this crate, since their generated code refers to ::turso_mappers
+extern crate self as turso_mappers;
+
+#[doc(hidden)]
+pub mod convert;
 
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
@@ -15,6 +31,11 @@ pub enum TursoMapperError {
     InvalidType(String),
     NullValue(String),
     ConversionError(String),
+    TooManyRows(String),
+    NoRows(String),
+    DuplicateKey(String),
+    RowLimitExceeded { limit: usize },
+    ArityMismatch { expected: usize, actual: usize },
     IoError(std::io::Error),
     TursoError(turso::Error),
 }
@@ -31,6 +52,13 @@ impl From<std::io::Error> for TursoMapperError {
     }
 }
 
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for TursoMapperError {
+    fn from(err: serde_json::Error) -> Self {
+        TursoMapperError::ConversionError(err.to_string())
+    }
+}
+
 impl std::fmt::Display for TursoMapperError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -38,401 +66,5737 @@ impl std::fmt::Display for TursoMapperError {
             TursoMapperError::InvalidType(msg) => write!(f, "Invalid type: {}", msg),
             TursoMapperError::NullValue(msg) => write!(f, "Null value: {}", msg),
             TursoMapperError::ConversionError(msg) => write!(f, "Conversion error: {}", msg),
+            TursoMapperError::TooManyRows(msg) => write!(f, "Too many rows: {}", msg),
+            TursoMapperError::NoRows(msg) => write!(f, "No rows: {}", msg),
+            TursoMapperError::DuplicateKey(msg) => write!(f, "Duplicate key: {}", msg),
+            TursoMapperError::RowLimitExceeded { limit } => write!(f, "Row limit exceeded: more than {} rows", limit),
+            TursoMapperError::ArityMismatch { expected, actual } => write!(f, "Arity mismatch: expected {} columns, got {}", expected, actual),
             TursoMapperError::IoError(err) => write!(f, "IO error: {}", err),
             TursoMapperError::TursoError(err) => write!(f, "Turso error: {}", err),
         }
     }
 }
 
+impl TursoMapperError {
+    /// Appends `context` in parentheses to the message of a `ConversionError`, `NullValue` or `DuplicateKey`, leaving
+    /// other errors unchanged.
+    pub fn with_context(self, context: &str) -> Self {
+        match self {
+            TursoMapperError::ConversionError(msg) => TursoMapperError::ConversionError(format!("{} ({})", msg, context)),
+            TursoMapperError::NullValue(msg) => TursoMapperError::NullValue(format!("{} ({})", msg, context)),
+            TursoMapperError::DuplicateKey(msg) => TursoMapperError::DuplicateKey(format!("{} ({})", msg, context)),
+            other => other,
+        }
+    }
+}
+
 impl std::error::Error for TursoMapperError {}
 
 pub type TursoMapperResult<T> = Result<T, TursoMapperError>;
 
+/// Maps rows with a function, which is `FnMut` so it can keep state across rows, such as a count or a set of the values
+/// seen so far. An error from the function has the index of the failing row appended to its message, e.g.
+/// "(row 48213)", when it is a `ConversionError`, `NullValue`, `ColumnNotFound`, `InvalidType` or `DuplicateKey`. The
+/// other errors are returned as they are: `ArityMismatch` has no message to extend, and `TursoError` and `IoError` come
+/// from turso or the IO rather than from mapping the row. Neither the mapping nor the mapped type needs to be `Send`,
+/// so rows can be mapped to types holding an `Rc` on a single threaded runtime; the returned future is `Send` when
+/// they are.
+///
+/// Implemented for `&mut turso::Rows` as well as `turso::Rows`, mapping the rows from the current position of the cursor,
+/// so the rest of the rows can be mapped by a later call, or by a function borrowing rows it does not own.
 pub trait MapRows {
     fn map_rows<F, T>(self, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
     where
-        F: Fn(turso::Row) -> TursoMapperResult<T>,
-        T: Send;
-}
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 
-impl MapRows for turso::Rows {
-    async fn map_rows<F, T>(mut self, f: F) -> TursoMapperResult<Vec<T>>
+    /// Maps rows with a function that also receives the index of each row, starting at 0.
+    fn map_rows_enumerate<F, T>(self, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
     where
-        F: Fn(turso::Row) -> TursoMapperResult<T>,
-        T: Send,
-    {
-        let mut rows = vec![];
+        F: FnMut(usize, turso::Row) -> TursoMapperResult<T>;
 
-        while let Some(row) = self.next().await? {
-            let t: T = f(row)?;
-            rows.push(t);
-        }
+    /// Maps rows like `map_rows` into a `Vec` allocated up front for `capacity` rows, for queries whose row count is
+    /// known roughly in advance, such as a page with a fixed `LIMIT`.
+    fn map_rows_with_capacity<F, T>(self, capacity: usize, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 
-        Ok(rows)
-    }
-}
+    /// Maps rows like `map_rows`, failing with `RowLimitExceeded` instead of reading on once `max_rows` rows have been
+    /// mapped and another is left, as a guard against a query returning far more rows than expected.
+    fn map_rows_limited<F, T>(self, max_rows: usize, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 
-pub trait TryFromRow: Send {
-    fn try_from_row(row: turso::Row, column_indices: ColumnIndices) -> TursoMapperResult<Self>
+    /// Maps at most `max_rows` rows, leaving any further rows unread, so that rows can be mapped in batches by calling it
+    /// again on the same `&mut Rows`.
+    fn map_rows_take<F, T>(self, max_rows: usize, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
     where
-        Self: Sized;
-}
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 
-pub trait QueryAs {
-    fn query_as<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    /// Maps rows into a collection chosen by the caller, such as a `HashSet` of distinct values, or a `BTreeMap` when the
+    /// function returns key and value pairs, e.g. `rows.map_rows_collect::<HashSet<_>, _, _>(f)`.
+    fn map_rows_collect<C, F, T>(self, f: F) -> impl Future<Output = TursoMapperResult<C>>
     where
-        T: TryFromRow + Send;
-}
+        C: Default + Extend<T>,
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 
-pub trait TryFromRowByIndex: Send {
-    fn try_from_row_by_index(row: turso::Row) -> TursoMapperResult<Self>
+    /// Maps rows into a `HashMap`, keyed by `key_fn` applied to each mapped value, such as its id. A key seen before fails
+    /// with `DuplicateKey`, holding the key and the index of the row.
+    fn map_rows_into_map<K, V, KF, VF>(self, key_fn: KF, value_fn: VF) -> impl Future<Output = TursoMapperResult<HashMap<K, V>>>
     where
-        Self: Sized;
-}
+        K: Eq + Hash + Debug,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>;
 
-pub trait QueryAsByIndex {
-    fn query_as_by_index<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    /// Maps rows into a `HashMap` like `map_rows_into_map`, except that a later row replaces an earlier one with the
+    /// same key.
+    fn map_rows_into_map_last_wins<K, V, KF, VF>(self, key_fn: KF, value_fn: VF) -> impl Future<Output = TursoMapperResult<HashMap<K, V>>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>;
+
+    /// Maps rows into a `HashMap` of the values sharing each key, such as order lines grouped by their order id, keeping
+    /// the rows of each group in the order they were read.
+    fn map_rows_grouped<K, V, KF, VF>(self, key_fn: KF, value_fn: VF) -> impl Future<Output = TursoMapperResult<HashMap<K, Vec<V>>>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>;
+
+    /// Maps rows with a function returning `None` for the rows to skip, such as soft deleted ones, keeping the other rows
+    /// in order. An error names the index of the failing row among all the rows read, not just the ones kept.
+    fn filter_map_rows<F, T>(self, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<Option<T>>;
+
+    /// Folds the rows into an accumulator as they are read, for aggregates too awkward to write in SQL, without
+    /// collecting the rows first. The first error stops the fold and names the index of the failing row.
+    fn try_fold_rows<F, A>(self, init: A, f: F) -> impl Future<Output = TursoMapperResult<A>>
+    where
+        F: FnMut(A, turso::Row) -> TursoMapperResult<A>;
+
+    /// Calls a function on each row as it is read, such as one writing the row to a file, without collecting anything.
+    /// The first error stops reading further rows and names the index of the failing row.
+    fn try_for_each_rows<F>(self, f: F) -> impl Future<Output = TursoMapperResult<()>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<()>;
+
+    /// Maps rows with an async function, such as one writing each row to an async sink. The future for a row is awaited
+    /// before the next row is read, so the rows keep their order and the first error stops the mapping.
+    fn map_rows_async<F, Fut, T>(self, f: F) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        F: FnMut(turso::Row) -> Fut,
+        Fut: Future<Output = TursoMapperResult<T>>;
+
+    /// Maps the rows with a function as the returned stream is polled, instead of collecting them into a `Vec`, so it
+    /// can be combined with stream adapters such as `chunks`. The stream ends after yielding the first error.
+    #[cfg(feature = "stream")]
+    fn map_rows_stream<F, T>(self, f: F) -> impl Stream<Item = TursoMapperResult<T>>
     where
-        T: TryFromRowByIndex + Send;
+        F: FnMut(turso::Row) -> TursoMapperResult<T>;
 }
 
-impl QueryAsByIndex for Connection {
-    async fn query_as_by_index<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+impl MapRows for &mut turso::Rows {
+    async fn map_rows<F, T>(self, mut f: F) -> TursoMapperResult<Vec<T>>
     where
-        T: TryFromRowByIndex + Send,
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
     {
-        let rows = self.query(sql, params).await?;
-        rows.map_rows(T::try_from_row_by_index).await
+        self.map_rows_enumerate(|_, row| f(row)).await
     }
-}
 
-pub struct ColumnIndices {
-    column_names: HashMap<String, usize>,
-}
+    async fn map_rows_enumerate<F, T>(self, mut f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(usize, turso::Row) -> TursoMapperResult<T>,
+    {
+        let mut rows = vec![];
 
-impl ColumnIndices {
-    pub fn new(columns: Vec<Column>) -> Self {
-        let column_names = columns
-            .iter()
-            .enumerate()
-            .map(|(i, column)| (column.name().to_string(), i))
-            .collect::<HashMap<String, usize>>();
+        while let Some(row) = self.next().await? {
+            let index = rows.len();
+            let t: T = f(index, row).map_err(|err| row_error(err, index))?;
+            rows.push(t);
+        }
 
-        ColumnIndices { column_names }
+        Ok(rows)
     }
 
-    pub fn get_index(&self, column_name: &str) -> Result<usize, TursoMapperError> {
-        self.column_names
-            .get(column_name)
-            .cloned()
-            .ok_or_else(|| TursoMapperError::ColumnNotFound(column_name.to_string()))
+    async fn map_rows_with_capacity<F, T>(self, capacity: usize, mut f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        self.try_fold_rows(Vec::with_capacity(capacity), |mut rows, row| {
+            rows.push(f(row)?);
+            Ok(rows)
+        })
+        .await
     }
-}
 
-pub trait TryFromRowByName {
-    fn try_from_row(row: turso::Row, column_indices: ColumnIndices) -> TursoMapperResult<Self>
+    async fn map_rows_limited<F, T>(self, max_rows: usize, mut f: F) -> TursoMapperResult<Vec<T>>
     where
-        Self: Sized;
-}
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        let mut rows = vec![];
 
-#[cfg(test)]
-mod tests {
-    use super::{ColumnIndices, QueryAsByIndex, TryFromRowByIndex, TursoMapperResult};
-    use crate::{MapRows, TursoMapperError};
-    use turso::{Builder, Row};
-    use turso_core::Value;
-    use turso_core::types::Text;
+        while let Some(row) = self.next().await? {
+            if rows.len() == max_rows {
+                return Err(TursoMapperError::RowLimitExceeded { limit: max_rows });
+            }
+            let t: T = f(row).map_err(|err| row_error(err, rows.len()))?;
+            rows.push(t);
+        }
 
-    struct CustomerWithManualTryFromRow {
-        id: i64,
-        name: String,
-        value: f64,
-        image: Vec<u8>,
+        Ok(rows)
     }
 
-    impl TryFromRowByIndex for CustomerWithManualTryFromRow {
-        fn try_from_row_by_index(row: Row) -> TursoMapperResult<Self> {
-            Ok(CustomerWithManualTryFromRow {
-                id: *row
-                    .get_value(0)?
-                    .as_integer()
-                    .ok_or_else(|| TursoMapperError::ConversionError("id is not an integer".to_string()))?,
-                name: row
-                    .get_value(1)?
-                    .as_text()
-                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
-                    .clone(),
-                value: *row
-                    .get_value(2)?
-                    .as_real()
-                    .ok_or_else(|| TursoMapperError::ConversionError("value is not a real".to_string()))?,
-                image: row
-                    .get_value(3)?
-                    .as_blob()
-                    .ok_or_else(|| TursoMapperError::ConversionError("image is not a blob".to_string()))?
-                    .clone(),
-            })
+    async fn map_rows_take<F, T>(self, max_rows: usize, mut f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        let mut rows = vec![];
+
+        while rows.len() < max_rows {
+            let Some(row) = self.next().await? else {
+                break;
+            };
+            let t: T = f(row).map_err(|err| row_error(err, rows.len()))?;
+            rows.push(t);
         }
-    }
 
-    #[derive(TryFromRowByIndex)]
-    struct Customer {
-        id: i64,
-        name: String,
-        value: f64,
-        image: Vec<u8>,
+        Ok(rows)
     }
 
-    #[derive(TryFromRowByIndex)]
-    struct CustomerWithOptions {
-        id: i64,
-        name: String,
-        optional_value: Option<f64>,
-        optional_note: Option<String>,
-        optional_data: Option<Vec<u8>>,
-        optional_count: Option<i64>,
+    async fn map_rows_collect<C, F, T>(self, mut f: F) -> TursoMapperResult<C>
+    where
+        C: Default + Extend<T>,
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        self.try_fold_rows(C::default(), |mut collection, row| {
+            collection.extend(Some(f(row)?));
+            Ok(collection)
+        })
+        .await
     }
 
-    #[tokio::test]
-    async fn can_get_values_using_map() -> TursoMapperResult<()> {
-        let db = Builder::new_local(":memory:").build().await?;
-        let conn = db.connect()?;
+    async fn map_rows_into_map<K, V, KF, VF>(self, mut key_fn: KF, mut value_fn: VF) -> TursoMapperResult<HashMap<K, V>>
+    where
+        K: Eq + Hash + Debug,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        self.try_fold_rows(HashMap::new(), |mut map, row| {
+            let value = value_fn(row)?;
+            match map.entry(key_fn(&value)) {
+                Entry::Occupied(entry) => Err(TursoMapperError::DuplicateKey(format!("{:?}", entry.key()))),
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                    Ok(map)
+                }
+            }
+        })
+        .await
+    }
 
-        conn.execute(
-            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
-            (),
-        )
-        .await?;
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
-            .await?;
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
-            .await?;
+    async fn map_rows_into_map_last_wins<K, V, KF, VF>(self, mut key_fn: KF, mut value_fn: VF) -> TursoMapperResult<HashMap<K, V>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        self.try_fold_rows(HashMap::new(), |mut map, row| {
+            let value = value_fn(row)?;
+            map.insert(key_fn(&value), value);
+            Ok(map)
+        })
+        .await
+    }
 
-        let rows = conn.query("SELECT id, name, value, image FROM customer;", ()).await?;
+    async fn map_rows_grouped<K, V, KF, VF>(self, mut key_fn: KF, mut value_fn: VF) -> TursoMapperResult<HashMap<K, Vec<V>>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        self.try_fold_rows(HashMap::new(), |mut groups: HashMap<K, Vec<V>>, row| {
+            let value = value_fn(row)?;
+            groups.entry(key_fn(&value)).or_default().push(value);
+            Ok(groups)
+        })
+        .await
+    }
 
-        let customer_names = rows
-            .map_rows(|row| {
+    async fn filter_map_rows<F, T>(self, mut f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<Option<T>>,
+    {
+        let mut rows = vec![];
+        let mut index = 0;
 
+        while let Some(row) = self.next().await? {
+            if let Some(t) = f(row).map_err(|err| row_error(err, index))? {
+                rows.push(t);
+            }
+            index += 1;
+        }
 
+        Ok(rows)
+    }
 
-                Ok(row
-                    .get_value(1)?
-                    .as_text()
-                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
-                    .clone())
-            })
-            .await?;
+    async fn try_fold_rows<F, A>(self, init: A, mut f: F) -> TursoMapperResult<A>
+    where
+        F: FnMut(A, turso::Row) -> TursoMapperResult<A>,
+    {
+        let mut acc = init;
+        let mut index = 0;
 
-        assert_eq!(customer_names.len(), 2);
+        while let Some(row) = self.next().await? {
+            acc = f(acc, row).map_err(|err| row_error(err, index))?;
+            index += 1;
+        }
 
-        assert_eq!(customer_names[0], "Charlie");
-        assert_eq!(customer_names[1], "Sarah");
+        Ok(acc)
+    }
 
-        Ok(())
+    async fn try_for_each_rows<F>(self, mut f: F) -> TursoMapperResult<()>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<()>,
+    {
+        self.try_fold_rows((), |(), row| f(row)).await
     }
 
-    #[tokio::test]
-    async fn can_get_values_using_map_with_names() -> TursoMapperResult<()> {
-        let db = Builder::new_local(":memory:").build().await?;
-        let conn = db.connect()?;
+    async fn map_rows_async<F, Fut, T>(self, mut f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> Fut,
+        Fut: Future<Output = TursoMapperResult<T>>,
+    {
+        let mut rows = vec![];
 
-        conn.execute(
-            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
-            (),
-        )
-        .await?;
+        while let Some(row) = self.next().await? {
+            let t: T = f(row).await.map_err(|err| row_error(err, rows.len()))?;
+            rows.push(t);
+        }
 
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
-            .await?;
+        Ok(rows)
+    }
 
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
-            .await?;
+    #[cfg(feature = "stream")]
+    fn map_rows_stream<F, T>(self, f: F) -> impl Stream<Item = TursoMapperResult<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        futures_util::stream::try_unfold((self, f, 0), |(rows, mut f, index)| async move {
+            match rows.next().await? {
+                Some(row) => {
+                    let t = f(row).map_err(|err| row_error(err, index))?;
+                    Ok(Some((t, (rows, f, index + 1))))
+                }
+                None => Ok(None),
+            }
+        })
+    }
+}
 
-        let mut statement = conn.prepare("SELECT id, name, value, image FROM customer;").await?;
-        let rows = statement.query(()).await?;
+// Owned rows are mapped through the implementation for `&mut turso::Rows`, except as a stream, which has to own them
+impl MapRows for turso::Rows {
+    async fn map_rows<F, T>(mut self, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows(f).await
+    }
 
-        let column_indices = ColumnIndices::new(statement.columns());
-        let name_column_index = column_indices.get_index("name")?;
+    async fn map_rows_enumerate<F, T>(mut self, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(usize, turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows_enumerate(f).await
+    }
 
-        let customer_names = rows
-            .map_rows(|row| {
-                Ok(row
-                    .get_value(name_column_index)?
-                    .as_text()
-                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
-                    .clone())
-            })
-            .await?;
+    async fn map_rows_with_capacity<F, T>(mut self, capacity: usize, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows_with_capacity(capacity, f).await
+    }
 
-        assert_eq!(customer_names.len(), 2);
+    async fn map_rows_limited<F, T>(mut self, max_rows: usize, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows_limited(max_rows, f).await
+    }
 
-        assert_eq!(customer_names[0], "Charlie");
-        assert_eq!(customer_names[1], "Sarah");
+    async fn map_rows_take<F, T>(mut self, max_rows: usize, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows_take(max_rows, f).await
+    }
 
-        Ok(())
+    async fn map_rows_collect<C, F, T>(mut self, f: F) -> TursoMapperResult<C>
+    where
+        C: Default + Extend<T>,
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        (&mut self).map_rows_collect(f).await
     }
 
-    #[tokio::test]
-    async fn manual_try_from_row_impl_works() -> TursoMapperResult<()> {
-        let row: Row = Row::from_iter(
-            [
-                Value::Integer(1),
-                Value::Text(Text::new("Charlie")),
-                Value::Float(3.12),
+    async fn map_rows_into_map<K, V, KF, VF>(mut self, key_fn: KF, value_fn: VF) -> TursoMapperResult<HashMap<K, V>>
+    where
+        K: Eq + Hash + Debug,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        (&mut self).map_rows_into_map(key_fn, value_fn).await
+    }
+
+    async fn map_rows_into_map_last_wins<K, V, KF, VF>(mut self, key_fn: KF, value_fn: VF) -> TursoMapperResult<HashMap<K, V>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        (&mut self).map_rows_into_map_last_wins(key_fn, value_fn).await
+    }
+
+    async fn map_rows_grouped<K, V, KF, VF>(mut self, key_fn: KF, value_fn: VF) -> TursoMapperResult<HashMap<K, Vec<V>>>
+    where
+        K: Eq + Hash,
+        KF: FnMut(&V) -> K,
+        VF: FnMut(turso::Row) -> TursoMapperResult<V>,
+    {
+        (&mut self).map_rows_grouped(key_fn, value_fn).await
+    }
+
+    async fn filter_map_rows<F, T>(mut self, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<Option<T>>,
+    {
+        (&mut self).filter_map_rows(f).await
+    }
+
+    async fn try_fold_rows<F, A>(mut self, init: A, f: F) -> TursoMapperResult<A>
+    where
+        F: FnMut(A, turso::Row) -> TursoMapperResult<A>,
+    {
+        (&mut self).try_fold_rows(init, f).await
+    }
+
+    async fn try_for_each_rows<F>(mut self, f: F) -> TursoMapperResult<()>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<()>,
+    {
+        (&mut self).try_for_each_rows(f).await
+    }
+
+    async fn map_rows_async<F, Fut, T>(mut self, f: F) -> TursoMapperResult<Vec<T>>
+    where
+        F: FnMut(turso::Row) -> Fut,
+        Fut: Future<Output = TursoMapperResult<T>>,
+    {
+        (&mut self).map_rows_async(f).await
+    }
+
+    #[cfg(feature = "stream")]
+    fn map_rows_stream<F, T>(self, f: F) -> impl Stream<Item = TursoMapperResult<T>>
+    where
+        F: FnMut(turso::Row) -> TursoMapperResult<T>,
+    {
+        futures_util::stream::try_unfold((self, f, 0), |(mut rows, mut f, index)| async move {
+            match rows.next().await? {
+                Some(row) => {
+                    let t = f(row).map_err(|err| row_error(err, index))?;
+                    Ok(Some((t, (rows, f, index + 1))))
+                }
+                None => Ok(None),
+            }
+        })
+    }
+}
+
+// Names the failing row in an error returned while mapping rows, for the errors with a message to append it to
+fn row_error(err: TursoMapperError, index: usize) -> TursoMapperError {
+    match err {
+        TursoMapperError::ColumnNotFound(msg) => TursoMapperError::ColumnNotFound(format!("{} (row {})", msg, index)),
+        TursoMapperError::InvalidType(msg) => TursoMapperError::InvalidType(format!("{} (row {})", msg, index)),
+        other => other.with_context(&format!("row {}", index)),
+    }
+}
+
+pub trait TryFromRowByIndex {
+    fn try_from_row_by_index(row: turso::Row) -> TursoMapperResult<Self>
+    where
+        Self: Sized;
+}
+
+/// Maps a struct from a run of columns starting part way through a row, so it can be embedded in another struct with
+/// `#[turso(flatten)]`. Implemented by `#[derive(TryFromRowByIndex)]`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened into a row mapping",
+    note = "derive `TryFromRowByIndex` for `{Self}` to use it with `#[turso(flatten)]`"
+)]
+pub trait FlattenRowByIndex: Sized {
+    /// The number of columns read from the row.
+    const COLUMN_COUNT: usize;
+
+    fn try_from_row_at_offset(row: &turso::Row, offset: usize) -> TursoMapperResult<Self>;
+}
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be mapped from a turso value",
+    note = "use a supported field type, derive `TryFromValue` for fieldless enums, or implement `TryFromValue` manually"
+)]
+pub trait TryFromValue: Sized {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self>;
+}
+
+// Names the value in the errors of the TryFromValue impls below, which are used to read scalars
+const SCALAR: &str = "scalar";
+
+fn unexpected_value(value: &turso::Value, expected: &str) -> TursoMapperError {
+    match value {
+        turso::Value::Null => TursoMapperError::NullValue(SCALAR.to_string()),
+        other => TursoMapperError::ConversionError(format!("{} is not {}, got {}", SCALAR, expected, convert::storage_class(other))),
+    }
+}
+
+impl TryFromValue for i64 {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Integer(value) => Ok(value),
+            turso::Value::Real(value) => convert::numeric::integer_from_real(value, SCALAR),
+            other => Err(unexpected_value(&other, "an integer or a real")),
+        }
+    }
+}
+
+macro_rules! integer_try_from_value {
+    ($($ty:ty),*) => {
+        $(impl TryFromValue for $ty {
+            fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+                let value = i64::try_from_value(value)?;
+                <$ty>::try_from(value).map_err(|_| {
+                    TursoMapperError::ConversionError(format!("{} value {} is out of range for {}", SCALAR, value, stringify!($ty)))
+                })
+            }
+        })*
+    };
+}
+
+integer_try_from_value!(i32, i16, i8, isize, u64, u32, u16, u8, usize);
+
+impl TryFromValue for f64 {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Real(value) => Ok(value),
+            turso::Value::Integer(value) => convert::numeric::real_from_integer(value, SCALAR),
+            other => Err(unexpected_value(&other, "a real or an integer")),
+        }
+    }
+}
+
+impl TryFromValue for f32 {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Real(value) => Ok(value as f32),
+            turso::Value::Integer(value) => convert::numeric::f32_from_integer(value, SCALAR),
+            other => Err(unexpected_value(&other, "a real or an integer")),
+        }
+    }
+}
+
+impl TryFromValue for bool {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Integer(0) => Ok(false),
+            turso::Value::Integer(1) => Ok(true),
+            turso::Value::Integer(other) => Err(TursoMapperError::ConversionError(format!(
+                "{} must be 0 or 1 to map to a bool, got {}",
+                SCALAR, other
+            ))),
+            other => Err(unexpected_value(&other, "an integer")),
+        }
+    }
+}
+
+impl TryFromValue for String {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Text(value) => Ok(value),
+            other => Err(unexpected_value(&other, "a string")),
+        }
+    }
+}
+
+impl TryFromValue for Vec<u8> {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Blob(value) => Ok(value),
+            other => Err(unexpected_value(&other, "a blob")),
+        }
+    }
+}
+
+impl<T: TryFromValue> TryFromValue for Option<T> {
+    fn try_from_value(value: turso::Value) -> TursoMapperResult<Self> {
+        match value {
+            turso::Value::Null => Ok(None),
+            value => T::try_from_value(value).map(Some),
+        }
+    }
+}
+
+/// Converts a field into the `turso::Value` it is bound as when the struct is used as query parameters.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be bound as a turso value",
+    note = "use a supported field type, mark the field `#[turso(skip)]`, or implement `ToValue` manually"
+)]
+pub trait ToValue {
+    fn to_value(&self) -> turso::Value;
+}
+
+macro_rules! integer_to_value {
+    ($($ty:ty),*) => {
+        $(impl ToValue for $ty {
+            fn to_value(&self) -> turso::Value {
+                turso::Value::Integer(i64::from(*self))
+            }
+        })*
+    };
+}
+
+integer_to_value!(i64, i32, i16, i8, u32, u16, u8, bool);
+
+// isize is at most 64 bits wide on every platform turso supports
+impl ToValue for isize {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Integer(*self as i64)
+    }
+}
+
+// Values above i64::MAX keep their bits as a negative integer, which a field marked #[turso(bit_cast)] reads back
+impl ToValue for u64 {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Integer(*self as i64)
+    }
+}
+
+impl ToValue for usize {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Integer(*self as u64 as i64)
+    }
+}
+
+impl ToValue for char {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Text(self.to_string())
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Real(*self)
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Real(f64::from(*self))
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Text(self.clone())
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Text(self.to_string())
+    }
+}
+
+impl ToValue for Vec<u8> {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Blob(self.clone())
+    }
+}
+
+impl ToValue for [u8] {
+    fn to_value(&self) -> turso::Value {
+        turso::Value::Blob(self.to_vec())
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> turso::Value {
+        match self {
+            Some(value) => value.to_value(),
+            None => turso::Value::Null,
+        }
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> turso::Value {
+        (**self).to_value()
+    }
+}
+
+/// Binds the fields of a struct as query parameters, so it can be passed to `execute` or `query`. Implemented by
+/// `#[derive(ToParams)]`.
+pub trait ToParams {
+    /// The field values in declaration order, for positional parameters such as `?1` or `?`.
+    fn to_params(&self) -> Vec<turso::Value>;
+
+    /// The field values paired with the parameter names `:field_name`, for named parameters.
+    fn to_named_params(&self) -> Vec<(String, turso::Value)>;
+
+    /// Consumes the struct and returns its positional parameters.
+    fn into_params(self) -> Vec<turso::Value>
+    where
+        Self: Sized,
+    {
+        self.to_params()
+    }
+}
+
+/// Inserts a struct into its table. Implemented by `#[derive(Insert)]`.
+pub trait Insert {
+    /// The INSERT statement for the struct, with a positional parameter for each column it writes.
+    fn insert_sql() -> &'static str;
+
+    /// The values bound to the parameters of `insert_sql`.
+    fn insert_params(&self) -> Vec<turso::Value>;
+
+    /// Inserts `row` and returns the rowid it was given.
+    fn insert(conn: &Connection, row: &Self) -> impl Future<Output = TursoMapperResult<i64>> {
+        async move {
+            conn.execute(Self::insert_sql(), row.insert_params()).await?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+/// Updates the row holding a struct, found by its primary key. Implemented by `#[derive(Update)]`.
+pub trait Update {
+    /// The UPDATE statement for the struct, with a positional parameter for each column it sets followed by one for
+    /// the primary key.
+    fn update_sql() -> &'static str;
+
+    /// The values bound to the parameters of `update_sql`.
+    fn update_params(&self) -> Vec<turso::Value>;
+
+    /// Updates the row with the struct's primary key and returns the number of rows affected.
+    fn update(&self, conn: &Connection) -> impl Future<Output = TursoMapperResult<u64>> {
+        async move { Ok(conn.execute(Self::update_sql(), self.update_params()).await?) }
+    }
+}
+
+/// Runs a query and maps each row with `TryFromRowByIndex`, the trait implemented by `#[derive(TryFromRowByIndex)]`.
+/// Implemented for `turso::Transaction` as well as `turso::Connection`, so the same methods work inside a transaction.
+pub trait QueryAs {
+    fn query_as<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs a query and maps each row with `TryFromRowByName`, the trait implemented by `#[derive(TryFromRowByName)]`,
+    /// looking up the columns once for the whole query, so the order of the columns in the SQL does not matter.
+    fn query_as_by_name<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByName;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex` into a `Vec` allocated up front for `capacity` rows.
+    fn query_as_with_capacity<T>(&self, sql: &str, params: impl IntoParams, capacity: usize) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex`, failing with `RowLimitExceeded` if it returns more than
+    /// `max_rows` rows rather than reading them all, e.g. as a guard against a missing `WHERE` clause.
+    fn query_as_limited<T>(&self, sql: &str, params: impl IntoParams, max_rows: usize) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex` into a collection chosen by the caller, such as a
+    /// `BTreeSet`, e.g. `conn.query_as_collect::<Tag, BTreeSet<_>>(sql, ())`.
+    fn query_as_collect<T, C>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<C>>
+    where
+        T: TryFromRowByIndex,
+        C: Default + Extend<T>;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex` into a `HashMap` keyed by `key_fn`, failing with
+    /// `DuplicateKey` if two rows have the same key, e.g. `conn.query_as_map(sql, (), |customer: &Customer| customer.id)`.
+    fn query_as_map<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> impl Future<Output = TursoMapperResult<HashMap<K, T>>>
+    where
+        T: TryFromRowByIndex,
+        K: Eq + Hash + Debug,
+        F: FnMut(&T) -> K;
+
+    /// Runs a query and maps each row into a `HashMap` like `query_as_map`, except that a later row replaces an earlier
+    /// one with the same key.
+    fn query_as_map_last_wins<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> impl Future<Output = TursoMapperResult<HashMap<K, T>>>
+    where
+        T: TryFromRowByIndex,
+        K: Eq + Hash,
+        F: FnMut(&T) -> K;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex` into a `HashMap` of the rows sharing each key, keeping the
+    /// order of the rows within each group, e.g. `conn.query_as_grouped(sql, (), |line: &OrderLine| line.order_id)`.
+    fn query_as_grouped<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> impl Future<Output = TursoMapperResult<HashMap<K, Vec<T>>>>
+    where
+        T: TryFromRowByIndex,
+        K: Eq + Hash,
+        F: FnMut(&T) -> K;
+
+    /// Runs a query and maps each row with `TryFromRowByIndex`, passing it to a function as it is read instead of
+    /// collecting the rows. The first error stops reading further rows.
+    fn for_each_as<T, F>(&self, sql: &str, params: impl IntoParams, f: F) -> impl Future<Output = TursoMapperResult<()>>
+    where
+        T: TryFromRowByIndex,
+        F: FnMut(T) -> TursoMapperResult<()>;
+
+    /// Runs a query returning exactly one row and maps it, failing with `NoRows` if it returns none and `TooManyRows`
+    /// if it returns more, both holding the SQL. No more than two rows are read.
+    fn query_one<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<T>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs a query returning at most one row and maps it, giving `None` if it returns no rows and failing with
+    /// `TooManyRows`, holding the SQL, if it returns more. No more than two rows are read.
+    fn query_optional<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Option<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs a query returning exactly one row and converts its first column with `TryFromValue`, failing like
+    /// `query_one` when there are no rows or more than one.
+    fn query_scalar<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<T>>
+    where
+        T: TryFromValue;
+
+    /// Runs a query returning at most one row and converts its first column with `TryFromValue`, giving `None` if it
+    /// returns no rows and failing like `query_optional` if it returns more.
+    fn query_scalar_optional<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Option<T>>>
+    where
+        T: TryFromValue;
+
+    /// Runs a query and converts the first column of every row with `TryFromValue`, such as the ids of the rows matching
+    /// a condition. A conversion error names the index of the failing row.
+    fn query_scalars<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromValue;
+
+    /// Runs a query and maps its rows one at a time as they are read, for result sets too large to hold in a `Vec`.
+    /// The query runs when the stream is first polled, and the stream ends after yielding the first error.
+    #[cfg(feature = "stream")]
+    fn query_as_stream<T>(&self, sql: &str, params: impl IntoParams) -> impl Stream<Item = TursoMapperResult<T>>
+    where
+        T: TryFromRowByIndex;
+}
+
+// Implemented for transactions as well as connections, both of which run queries with `query` and `prepare`
+macro_rules! query_as_impl {
+    ($($ty:ty),*) => {
+        $(impl QueryAs for $ty {
+            async fn query_as<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                self.query_as_by_index(sql, params).await
+            }
+
+            async fn query_as_by_name<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromRowByName,
+            {
+                let mut statement = self.prepare(sql).await?;
+                statement.query_as_by_name(params).await
+            }
+
+            async fn query_as_with_capacity<T>(&self, sql: &str, params: impl IntoParams, capacity: usize) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_with_capacity(capacity, T::try_from_row_by_index).await
+            }
+
+            async fn query_as_limited<T>(&self, sql: &str, params: impl IntoParams, max_rows: usize) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_limited(max_rows, T::try_from_row_by_index).await
+            }
+
+            async fn query_as_collect<T, C>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<C>
+            where
+                T: TryFromRowByIndex,
+                C: Default + Extend<T>,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_collect(T::try_from_row_by_index).await
+            }
+
+            async fn query_as_map<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> TursoMapperResult<HashMap<K, T>>
+            where
+                T: TryFromRowByIndex,
+                K: Eq + Hash + Debug,
+                F: FnMut(&T) -> K,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_into_map(key_fn, T::try_from_row_by_index).await
+            }
+
+            async fn query_as_map_last_wins<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> TursoMapperResult<HashMap<K, T>>
+            where
+                T: TryFromRowByIndex,
+                K: Eq + Hash,
+                F: FnMut(&T) -> K,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_into_map_last_wins(key_fn, T::try_from_row_by_index).await
+            }
+
+            async fn query_as_grouped<T, K, F>(&self, sql: &str, params: impl IntoParams, key_fn: F) -> TursoMapperResult<HashMap<K, Vec<T>>>
+            where
+                T: TryFromRowByIndex,
+                K: Eq + Hash,
+                F: FnMut(&T) -> K,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows_grouped(key_fn, T::try_from_row_by_index).await
+            }
+
+            async fn for_each_as<T, F>(&self, sql: &str, params: impl IntoParams, mut f: F) -> TursoMapperResult<()>
+            where
+                T: TryFromRowByIndex,
+                F: FnMut(T) -> TursoMapperResult<()>,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.try_for_each_rows(|row| f(T::try_from_row_by_index(row)?)).await
+            }
+
+            async fn query_one<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<T>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = self.query(sql, params).await?;
+                match single_row(rows, sql).await? {
+                    Some(row) => T::try_from_row_by_index(row),
+                    None => Err(TursoMapperError::NoRows(sql.to_string())),
+                }
+            }
+
+            async fn query_optional<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Option<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = self.query(sql, params).await?;
+                single_row(rows, sql).await?.map(T::try_from_row_by_index).transpose()
+            }
+
+            async fn query_scalar<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<T>
+            where
+                T: TryFromValue,
+            {
+                let rows = self.query(sql, params).await?;
+                match single_row(rows, sql).await? {
+                    Some(row) => scalar(&row),
+                    None => Err(TursoMapperError::NoRows(sql.to_string())),
+                }
+            }
+
+            async fn query_scalar_optional<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Option<T>>
+            where
+                T: TryFromValue,
+            {
+                let rows = self.query(sql, params).await?;
+                single_row(rows, sql).await?.map(|row| scalar(&row)).transpose()
+            }
+
+            async fn query_scalars<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromValue,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows(|row| scalar(&row)).await
+            }
+
+            #[cfg(feature = "stream")]
+            fn query_as_stream<T>(&self, sql: &str, params: impl IntoParams) -> impl Stream<Item = TursoMapperResult<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = async move { Ok::<_, TursoMapperError>(self.query(sql, params).await?) };
+                futures_util::stream::once(rows)
+                    .map_ok(|rows| rows.map_rows_stream(T::try_from_row_by_index))
+                    .try_flatten()
+            }
+        })*
+    };
+}
+
+query_as_impl!(Connection, turso::Transaction<'_>);
+
+// Converts the first column of a row, the shape of a query selecting a single value
+fn scalar<T: TryFromValue>(row: &turso::Row) -> TursoMapperResult<T> {
+    T::try_from_value(convert::get_value(row, 0)?)
+}
+
+// Reads the only row of a query, if any, failing without reading further once a second row shows up
+async fn single_row(mut rows: turso::Rows, sql: &str) -> TursoMapperResult<Option<turso::Row>> {
+    let Some(row) = rows.next().await? else {
+        return Ok(None);
+    };
+    if rows.next().await?.is_some() {
+        return Err(TursoMapperError::TooManyRows(sql.to_string()));
+    }
+    Ok(Some(row))
+}
+
+pub trait QueryAsByIndex {
+    fn query_as_by_index<T>(&self, sql: &str, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByIndex;
+}
+
+macro_rules! query_as_by_index_impl {
+    ($($ty:ty),*) => {
+        $(impl QueryAsByIndex for $ty {
+            async fn query_as_by_index<T>(&self, sql: &str, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+            where
+                T: TryFromRowByIndex,
+            {
+                let rows = self.query(sql, params).await?;
+                rows.map_rows(T::try_from_row_by_index).await
+            }
+        })*
+    };
+}
+
+query_as_by_index_impl!(Connection, turso::Transaction<'_>);
+
+// Stands in for the SQL in the errors of a prepared statement, which does not expose its SQL
+const PREPARED_STATEMENT: &str = "prepared statement";
+
+/// Runs a prepared statement and maps its rows, for statements prepared once and run many times with different
+/// parameters. `NoRows` and `TooManyRows` hold "prepared statement" rather than the SQL.
+pub trait StatementQueryAs {
+    fn query_as<T>(&mut self, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs the statement and maps each row with `TryFromRowByName`, looking up the columns of the statement once.
+    fn query_as_by_name<T>(&mut self, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Vec<T>>>
+    where
+        T: TryFromRowByName;
+
+    /// Runs the statement and maps its only row, failing with `NoRows` or `TooManyRows` like `QueryAs::query_one`.
+    fn query_one<T>(&mut self, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<T>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs the statement and maps its row if it returns one, failing with `TooManyRows` like `QueryAs::query_optional`.
+    fn query_optional<T>(&mut self, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<Option<T>>>
+    where
+        T: TryFromRowByIndex;
+
+    /// Runs the statement and converts the first column of its only row with `TryFromValue`.
+    fn query_scalar<T>(&mut self, params: impl IntoParams) -> impl Future<Output = TursoMapperResult<T>>
+    where
+        T: TryFromValue;
+}
+
+impl StatementQueryAs for turso::Statement {
+    async fn query_as<T>(&mut self, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+    where
+        T: TryFromRowByIndex,
+    {
+        let rows = self.query(params).await?;
+        rows.map_rows(T::try_from_row_by_index).await
+    }
+
+    async fn query_as_by_name<T>(&mut self, params: impl IntoParams) -> TursoMapperResult<Vec<T>>
+    where
+        T: TryFromRowByName,
+    {
+        let rows = self.query(params).await?;
+        let column_indices = ColumnIndices::new(self.columns());
+        rows.map_rows(|row| T::try_from_row(row, &column_indices)).await
+    }
+
+    async fn query_one<T>(&mut self, params: impl IntoParams) -> TursoMapperResult<T>
+    where
+        T: TryFromRowByIndex,
+    {
+        let rows = self.query(params).await?;
+        match single_row(rows, PREPARED_STATEMENT).await? {
+            Some(row) => T::try_from_row_by_index(row),
+            None => Err(TursoMapperError::NoRows(PREPARED_STATEMENT.to_string())),
+        }
+    }
+
+    async fn query_optional<T>(&mut self, params: impl IntoParams) -> TursoMapperResult<Option<T>>
+    where
+        T: TryFromRowByIndex,
+    {
+        let rows = self.query(params).await?;
+        single_row(rows, PREPARED_STATEMENT).await?.map(T::try_from_row_by_index).transpose()
+    }
+
+    async fn query_scalar<T>(&mut self, params: impl IntoParams) -> TursoMapperResult<T>
+    where
+        T: TryFromValue,
+    {
+        let rows = self.query(params).await?;
+        match single_row(rows, PREPARED_STATEMENT).await? {
+            Some(row) => scalar(&row),
+            None => Err(TursoMapperError::NoRows(PREPARED_STATEMENT.to_string())),
+        }
+    }
+}
+
+pub struct ColumnIndices {
+    column_names: HashMap<String, usize>,
+}
+
+impl ColumnIndices {
+    pub fn new(columns: Vec<Column>) -> Self {
+        let column_names = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (column.name().to_string(), i))
+            .collect::<HashMap<String, usize>>();
+
+        ColumnIndices { column_names }
+    }
+
+    pub fn get_index(&self, column_name: &str) -> Result<usize, TursoMapperError> {
+        self.column_names
+            .get(column_name)
+            .cloned()
+            .ok_or_else(|| TursoMapperError::ColumnNotFound(column_name.to_string()))
+    }
+
+    /// Looks up the column named `column_name` with `prefix` prepended, reporting the prefixed name if it is missing.
+    pub fn get_prefixed_index(&self, prefix: &str, column_name: &str) -> Result<usize, TursoMapperError> {
+        if prefix.is_empty() {
+            return self.get_index(column_name);
+        }
+        self.get_index(&format!("{}{}", prefix, column_name))
+    }
+}
+
+/// A marker for types mapped both by index and by name, implemented for every type implementing `TryFromRowByIndex` and
+/// `TryFromRowByName`, such as one with `#[derive(TryFromRow)]`, for a bound requiring both mappings.
+pub trait TryFromRow: TryFromRowByIndex + TryFromRowByName {}
+
+impl<T: TryFromRowByIndex + TryFromRowByName> TryFromRow for T {}
+
+/// Maps a row by column name, looking each field up in `column_indices`, which is built once from the columns of the
+/// statement and shared by all of its rows, so it is borrowed rather than taken by value.
+pub trait TryFromRowByName {
+    fn try_from_row(row: turso::Row, column_indices: &ColumnIndices) -> TursoMapperResult<Self>
+    where
+        Self: Sized;
+}
+
+/// Maps a struct by name from a row shared with another struct that embeds it with `#[turso(flatten)]`, reading
+/// each field from the column named with `prefix` prepended. Implemented by `#[derive(TryFromRowByName)]`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened into a row mapping by name",
+    note = "derive `TryFromRowByName` for `{Self}` to use it with `#[turso(flatten)]`"
+)]
+pub trait FlattenRowByName: Sized {
+    fn try_from_row_with_prefix(row: &turso::Row, column_indices: &ColumnIndices, prefix: &str) -> TursoMapperResult<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ColumnIndices, Insert, QueryAs, QueryAsByIndex, StatementQueryAs, ToParams, ToValue, TryFromRow, TryFromRowByIndex, TryFromRowByName, TryFromValue,
+        TursoMapperResult, Update,
+    };
+    use crate::{MapRows, TursoMapperError};
+    #[cfg(feature = "bigdecimal")]
+    use bigdecimal::BigDecimal;
+    #[cfg(feature = "bytes")]
+    use bytes::Bytes;
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    #[cfg(feature = "stream")]
+    use futures_util::{StreamExt, TryStreamExt};
+    #[cfg(feature = "jiff")]
+    use jiff::{Timestamp, Zoned};
+    #[cfg(feature = "semver")]
+    use semver::Version;
+    use std::borrow::Cow;
+    #[cfg(feature = "stream")]
+    use std::cell::Cell;
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::marker::PhantomData;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::num::{NonZero, NonZeroI64, NonZeroU32};
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::time::Duration;
+    #[cfg(feature = "time")]
+    use time::{OffsetDateTime, PrimitiveDateTime, macros::datetime};
+    use turso::{Builder, Row};
+    use turso_core::Value;
+    use turso_core::types::Text;
+    #[cfg(feature = "uuid")]
+    use uuid::Uuid;
+
+    struct CustomerWithManualTryFromRow {
+        id: i64,
+        name: String,
+        value: f64,
+        image: Vec<u8>,
+    }
+
+    impl TryFromRowByIndex for CustomerWithManualTryFromRow {
+        fn try_from_row_by_index(row: Row) -> TursoMapperResult<Self> {
+            Ok(CustomerWithManualTryFromRow {
+                id: *row
+                    .get_value(0)?
+                    .as_integer()
+                    .ok_or_else(|| TursoMapperError::ConversionError("id is not an integer".to_string()))?,
+                name: row
+                    .get_value(1)?
+                    .as_text()
+                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
+                    .clone(),
+                value: *row
+                    .get_value(2)?
+                    .as_real()
+                    .ok_or_else(|| TursoMapperError::ConversionError("value is not a real".to_string()))?,
+                image: row
+                    .get_value(3)?
+                    .as_blob()
+                    .ok_or_else(|| TursoMapperError::ConversionError("image is not a blob".to_string()))?
+                    .clone(),
+            })
+        }
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Customer {
+        id: i64,
+        name: String,
+        value: f64,
+        image: Vec<u8>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithOptions {
+        id: i64,
+        name: String,
+        optional_value: Option<f64>,
+        optional_note: Option<String>,
+        optional_data: Option<Vec<u8>>,
+        optional_count: Option<i64>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithFlags {
+        id: i64,
+        active: bool,
+        verified: Option<bool>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithSmallIds {
+        id: i32,
+        parent_id: Option<i32>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct OrderStatusCodes {
+        status: i16,
+        priority: i8,
+        optional_status: Option<i16>,
+        optional_priority: Option<i8>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct ServerStats {
+        port: u16,
+        count: u32,
+        flags: u8,
+        optional_port: Option<u16>,
+        optional_count: Option<u32>,
+        optional_flags: Option<u8>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct ContentHashes {
+        size: u64,
+        optional_size: Option<u64>,
+        #[turso(bit_cast)]
+        hash: u64,
+        #[turso(bit_cast)]
+        optional_hash: Option<u64>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct SensorReading {
+        temperature: f32,
+        humidity: Option<f32>,
+        #[turso(strict_float)]
+        pressure: f32,
+        #[turso(strict_float)]
+        optional_pressure: Option<f32>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerOrderCount {
+        name: String,
+        order_count: usize,
+        balance_delta: isize,
+        optional_order_count: Option<usize>,
+        optional_balance_delta: Option<isize>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct LegacyCodes {
+        code: char,
+        optional_code: Option<char>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithNonZeroIds {
+        id: NonZeroI64,
+        account_id: NonZeroU32,
+        region_id: NonZero<u8>,
+        parent_id: Option<NonZeroI64>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct SharedCustomer {
+        name: Arc<str>,
+        email: Box<str>,
+        nickname: Option<Arc<str>>,
+        note: Option<Box<str>>,
+    }
+
+    // Holds Rc values, so it is not Send
+    #[derive(TryFromRowByIndex)]
+    struct LocalCustomer {
+        #[turso(try_from = "String")]
+        name: Rc<str>,
+        #[turso(try_from = "String")]
+        email: Rc<String>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithCow {
+        name: Cow<'static, str>,
+        email: std::borrow::Cow<'static, str>,
+        nickname: Option<Cow<'static, str>>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Attachment {
+        data: Box<[u8]>,
+        thumbnail: Option<Box<[u8]>>,
+    }
+
+    const KEY_LEN: usize = 16;
+
+    #[derive(TryFromRowByIndex)]
+    struct KeyMaterial {
+        hash: [u8; 32],
+        key: [u8; KEY_LEN],
+        previous_hash: Option<[u8; 32]>,
+    }
+
+    #[cfg(feature = "chrono")]
+    #[derive(TryFromRowByIndex)]
+    struct AuditedRecord {
+        created_at: DateTime<Utc>,
+        updated_at: NaiveDateTime,
+        deleted_at: Option<DateTime<Utc>>,
+        archived_at: Option<NaiveDateTime>,
+    }
+
+    #[cfg(feature = "chrono")]
+    #[derive(TryFromRowByIndex)]
+    struct Holiday {
+        date: NaiveDate,
+        observed_on: Option<NaiveDate>,
+    }
+
+    #[cfg(feature = "chrono")]
+    #[derive(TryFromRowByIndex)]
+    struct OpeningHours {
+        opens_at: NaiveTime,
+        closes_at: NaiveTime,
+        last_entry_at: Option<NaiveTime>,
+    }
+
+    #[cfg(feature = "time")]
+    #[derive(TryFromRowByIndex)]
+    struct ScheduledJob {
+        scheduled_at: OffsetDateTime,
+        local_run_at: PrimitiveDateTime,
+        finished_at: Option<OffsetDateTime>,
+        local_finished_at: Option<PrimitiveDateTime>,
+    }
+
+    #[cfg(feature = "jiff")]
+    #[derive(TryFromRowByIndex)]
+    struct Meeting {
+        starts_at: Timestamp,
+        local_start: Zoned,
+        ended_at: Option<Timestamp>,
+        local_end: Option<Zoned>,
+    }
+
+    #[cfg(feature = "uuid")]
+    #[derive(TryFromRowByIndex)]
+    struct Session {
+        id: Uuid,
+        legacy_id: Uuid,
+        parent_id: Option<Uuid>,
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    #[turso(rename_all = "snake_case")]
+    enum OrderStatus {
+        Pending,
+        Shipped,
+        OnHold,
+        #[turso(rename = "canceled")]
+        Cancelled,
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    #[repr(i64)]
+    enum Priority {
+        Low = 0,
+        Normal = 1,
+        Urgent = 5,
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    enum Visibility {
+        #[turso(value = -1)]
+        Hidden,
+        #[turso(value = 10)]
+        Public,
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    #[turso(rename_all = "lowercase")]
+    enum DeliveryChannel {
+        Email,
+        Sms,
+        #[turso(other)]
+        Unknown(String),
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    enum Region {
+        #[turso(value = 1)]
+        Europe,
+        #[turso(value = 2)]
+        Americas,
+        #[turso(other)]
+        Unassigned,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithQualifiedOptions {
+        id: i64,
+        description: std::option::Option<String>,
+        referrer_id: ::core::option::Option<i64>,
+    }
+
+    extern crate alloc;
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithQualifiedTypes {
+        id: ::std::primitive::i64,
+        name: std::string::String,
+        nickname: alloc::string::String,
+        image: std::vec::Vec<u8>,
+        thumbnail: Option<alloc::boxed::Box<[u8]>>,
+        description: std::borrow::Cow<'static, str>,
+        home: std::path::PathBuf,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct RetryPolicy {
+        timeout: Duration,
+        #[turso(unit = "millis")]
+        backoff: Duration,
+        #[turso(unit = "micros")]
+        jitter: Option<Duration>,
+        #[turso(unit = "nanos")]
+        resolution: Duration,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct ImportedNote {
+        #[turso(lossy_text)]
+        title: String,
+        #[turso(lossy_text)]
+        body: Option<String>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct LegacyNote {
+        #[turso(text_from_blob)]
+        title: String,
+        #[turso(text_from_blob)]
+        body: Option<String>,
+        author: String,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct CustomerSummary {
+        id: i64,
+        #[turso(rename = "name")]
+        customer_name: String,
+        #[turso(rename = "name")]
+        display_name: String,
+        description: Option<String>,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct CustomerWithComputedFields {
+        #[turso(skip)]
+        display_name_cache: Option<String>,
+        id: i64,
+        #[turso(skip_with = "String::from(\"unknown\")")]
+        source: String,
+        name: String,
+        #[turso(skip)]
+        marker: std::marker::PhantomData<u8>,
+        description: Option<String>,
+    }
+
+    fn default_credit_limit() -> i64 {
+        1000
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct CustomerWithDefaults {
+        id: i64,
+        #[turso(default)]
+        name: String,
+        #[turso(default = "default_credit_limit")]
+        credit_limit: i64,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct CustomerWithLegacyNulls {
+        id: i64,
+        #[turso(null_as_default)]
+        name: String,
+        #[turso(null_as_default, default = "default_credit_limit")]
+        credit_limit: i64,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Permissions {
+        read: bool,
+        write: bool,
+    }
+
+    // Permissions are packed into the low bits of an INTEGER column
+    fn unpack_permissions(value: turso::Value) -> TursoMapperResult<Permissions> {
+        match value {
+            turso::Value::Integer(bits) if bits & !0b11 == 0 => Ok(Permissions {
+                read: bits & 0b01 != 0,
+                write: bits & 0b10 != 0,
+            }),
+            other => Err(TursoMapperError::ConversionError(format!("invalid permission bits {:?}", other))),
+        }
+    }
+
+    // Legacy nicknames are stored reversed, with an empty string for none
+    fn legacy_nickname(value: turso::Value) -> TursoMapperResult<Option<String>> {
+        match value {
+            turso::Value::Text(text) if text.is_empty() => Ok(None),
+            turso::Value::Text(text) => Ok(Some(text.chars().rev().collect())),
+            turso::Value::Null => Ok(None),
+            other => Err(TursoMapperError::ConversionError(format!("invalid nickname {:?}", other))),
+        }
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct AccountWithPermissions {
+        id: i64,
+        #[turso(with = "unpack_permissions")]
+        permissions: Permissions,
+        #[turso(with = "legacy_nickname")]
+        nickname: Option<String>,
+    }
+
+    #[derive(TryFromRow)]
+    struct AccountSummary {
+        id: i64,
+        #[turso(rename = "name")]
+        account_name: String,
+        #[turso(with = "unpack_permissions")]
+        permissions: Permissions,
+        #[turso(default)]
+        balance: f64,
+        nickname: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct EmailAddress(String);
+
+    impl TryFrom<String> for EmailAddress {
+        type Error = String;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            if value.contains('@') {
+                Ok(EmailAddress(value))
+            } else {
+                Err(format!("{:?} is not an email address", value))
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Percentage(u8);
+
+    impl TryFrom<i64> for Percentage {
+        type Error = String;
+
+        fn try_from(value: i64) -> Result<Self, Self::Error> {
+            match u8::try_from(value) {
+                Ok(value) if value <= 100 => Ok(Percentage(value)),
+                _ => Err(format!("{} is not a percentage", value)),
+            }
+        }
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Subscriber {
+        #[turso(try_from = "String")]
+        email: EmailAddress,
+        #[turso(try_from = "String")]
+        backup_email: Option<EmailAddress>,
+        #[turso(try_from = "i64")]
+        discount: Percentage,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct Address {
+        street: String,
+        city: String,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    struct ContactDetails {
+        email: String,
+        #[turso(flatten)]
+        address: Address,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithContactDetails {
+        #[turso(flatten)]
+        customer: CustomerWithIdLast,
+        #[turso(skip)]
+        display_name: String,
+        #[turso(flatten)]
+        contact: ContactDetails,
+        order_count: i64,
+    }
+
+    #[derive(TryFromRowByName)]
+    struct CustomerWithAddresses {
+        id: i64,
+        name: String,
+        #[turso(flatten, prefix = "home_")]
+        home: Address,
+        #[turso(flatten, prefix = "work_")]
+        work: ContactDetails,
+    }
+
+    #[derive(TryFromRowByName)]
+    #[turso(rename_all = "camelCase")]
+    struct JsServiceCustomer {
+        customer_id: i64,
+        display_name: String,
+        #[turso(rename = "HTMLProfileURL")]
+        profile_url: Option<String>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct MeasurementWithLenientOptions {
+        reading: Option<f64>,
+        #[turso(lenient)]
+        note: Option<String>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(lenient)]
+    struct LenientCustomer {
+        id: i64,
+        description: Option<String>,
+        #[turso(strict)]
+        rating: Option<i64>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, TryFromRowByIndex)]
+    struct Tag {
+        name: String,
+    }
+
+    #[cfg(feature = "stream")]
+    thread_local! {
+        static MAPPED_ROWS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // Counts the rows mapped so far on this thread, to check that streams map rows only as they are polled
+    #[cfg(feature = "stream")]
+    struct CountedId(i64);
+
+    #[cfg(feature = "stream")]
+    impl TryFromRowByIndex for CountedId {
+        fn try_from_row_by_index(row: Row) -> TursoMapperResult<Self> {
+            MAPPED_ROWS.set(MAPPED_ROWS.get() + 1);
+            Ok(CountedId(i64::try_from_value(row.get_value(0)?)?))
+        }
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct AggregateCounts {
+        total: i64,
+        tiny: i8,
+        small: i16,
+        medium: i32,
+        size: isize,
+        unsigned_tiny: u8,
+        unsigned_small: u16,
+        unsigned_medium: u32,
+        unsigned: u64,
+        unsigned_size: usize,
+        non_zero: NonZeroU32,
+        optional: Option<i64>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct LegacyInvoice {
+        id: i64,
+        #[turso(parse_text)]
+        quantity: u8,
+        #[turso(parse_text)]
+        amount: Option<f64>,
+        reference: String,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(parse_text)]
+    struct LegacyTotals {
+        count: i64,
+        average: f32,
+        label: String,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct LegacyFlags {
+        #[turso(bool_text = "Y/N")]
+        active: bool,
+        #[turso(bool_text = "true/false")]
+        verified: Option<bool>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(coerce)]
+    struct CoercedTotals {
+        total: i64,
+        average: f64,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(coerce, parse_text)]
+    struct AdHocReport {
+        total: i64,
+        average: f64,
+        #[turso(coerce = false)]
+        row_count: i64,
+        label: String,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(coerce = false)]
+    struct ExactReading {
+        value: f64,
+        #[turso(parse_text)]
+        count: i64,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct CoercedReading {
+        #[turso(coerce)]
+        value: f64,
+        #[turso(coerce)]
+        weight: f32,
+        #[turso(coerce)]
+        count: i64,
+        #[turso(coerce)]
+        small_count: u8,
+        #[turso(coerce)]
+        limit: Option<i32>,
+    }
+
+    fn not_blank(value: &str) -> Result<(), String> {
+        if value.trim().is_empty() {
+            Err("must not be blank".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn positive(value: &f64) -> Result<(), &'static str> {
+        if *value > 0.0 { Ok(()) } else { Err("must be positive") }
+    }
+
+    fn valid_discount(value: &Option<i64>) -> Result<(), String> {
+        match value {
+            Some(percent) if !(0..=100).contains(percent) => Err(format!("{} is not a percentage", percent)),
+            _ => Ok(()),
+        }
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct ValidatedOrder {
+        #[turso(validate = "not_blank")]
+        reference: String,
+        #[turso(validate = "positive")]
+        amount: f64,
+        #[turso(validate = "valid_discount")]
+        discount: Option<i64>,
+    }
+
+    #[derive(Debug, PartialEq, Insert, TryFromRowByIndex, Update)]
+    #[turso(table = "customer")]
+    struct StoredCustomer {
+        #[turso(primary_key, auto)]
+        id: i64,
+        #[turso(rename = "name")]
+        customer_name: String,
+        value: f64,
+        description: Option<String>,
+        #[turso(skip)]
+        cached_total: i64,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(deny_extra_columns)]
+    struct CustomerWithAddressColumns {
+        id: i64,
+        #[turso(flatten)]
+        address: Address,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct BillingLine {
+        #[turso(error_context = "customer id from billing report")]
+        customer_id: i64,
+        #[turso(strict, error_context = "customer value from billing report")]
+        value: Option<f64>,
+        #[turso(validate = "positive", error_context = "amount from billing report")]
+        amount: f64,
+    }
+
+    #[derive(Debug, PartialEq, TryFromRow)]
+    #[turso(tag = "kind", rename_all = "snake_case")]
+    enum Event {
+        Click {
+            x: i64,
+            y: i64,
+        },
+        #[turso(rename = "purchase")]
+        BasketPurchase {
+            #[turso(rename = "amount")]
+            total: f64,
+            note: Option<String>,
+        },
+        PageView,
+    }
+
+    #[derive(Debug, PartialEq, TryFromRowByName)]
+    #[turso(tag = "channel")]
+    enum Alert {
+        Email {
+            address: String,
+        },
+        #[turso(other)]
+        Unsupported,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    #[turso(table = "visit")]
+    struct Visit {
+        #[turso(primary_key)]
+        customer_id: i64,
+        page: String,
+    }
+
+    struct CustomerMarker;
+
+    #[derive(Debug, PartialEq, TryFromRow)]
+    struct RowExists {}
+
+    #[derive(Debug, PartialEq, TryFromRow)]
+    struct Probe;
+
+    #[derive(Debug, PartialEq, TryFromRowByIndex, TryFromRowByName)]
+    struct TypedRecord<Marker> {
+        id: i64,
+        name: String,
+        _marker: PhantomData<Marker>,
+    }
+
+    #[derive(Debug, PartialEq, TryFromRowByIndex)]
+    struct FixedDigest<const N: usize> {
+        id: i64,
+        digest: [u8; N],
+    }
+
+    #[derive(TryFromRow)]
+    #[turso(table = "customer")]
+    struct CustomerRecord {
+        id: i64,
+        name: String,
+    }
+
+    #[derive(TryFromRowByName)]
+    #[turso(table = "customer_note")]
+    struct CustomerNote {
+        note: String,
+    }
+
+    #[derive(ToParams, TryFromRowByIndex)]
+    struct NewCustomer {
+        name: String,
+        #[turso(rename = "value")]
+        balance: f64,
+        image: Vec<u8>,
+        description: Option<String>,
+        #[turso(skip)]
+        cached_total: i64,
+    }
+
+    // Stands in for a facade crate re-exporting turso-mappers
+    mod facade {
+        pub use crate as mappers;
+    }
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    #[turso(crate = "facade::mappers")]
+    enum Tier {
+        Free,
+        Paid,
+    }
+
+    #[derive(TryFromRowByIndex, TryFromRowByName)]
+    #[turso(crate = "facade::mappers")]
+    struct CustomerWithTier {
+        id: i64,
+        tier: Tier,
+    }
+
+    // Shares a struct with a query selecting the id last
+    #[derive(TryFromRowByIndex)]
+    struct CustomerWithIdLast {
+        #[turso(index = 2)]
+        id: i64,
+        name: String,
+        description: Option<String>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Token {
+        r#type: String,
+        r#match: Option<i64>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Point(f64, f64);
+
+    #[derive(TryFromRowByIndex)]
+    struct IdAndName(i64, Option<String>);
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    struct CustomerId(i64);
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    struct Email(String);
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    struct LoyaltyPoints(u32);
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    struct Audited<T: TryFromValue>(T);
+
+    #[derive(Debug, PartialEq, TryFromValue)]
+    struct Digest<const N: usize>([u8; N]);
+
+    #[derive(TryFromRowByIndex)]
+    struct CustomerContact {
+        customer_id: CustomerId,
+        email: Email,
+        backup_email: Option<Email>,
+        points: LoyaltyPoints,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Notification {
+        channel: DeliveryChannel,
+        fallback_channel: Option<DeliveryChannel>,
+        region: Region,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct Ticket {
+        priority: Priority,
+        escalated_priority: Option<Priority>,
+        visibility: Visibility,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct OrderWithStatus {
+        id: i64,
+        status: OrderStatus,
+        previous_status: Option<OrderStatus>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct StoredFile {
+        path: PathBuf,
+        backup_path: Option<std::path::PathBuf>,
+    }
+
+    #[derive(TryFromRowByIndex)]
+    struct AuditEntry {
+        client_ip: IpAddr,
+        gateway: Ipv4Addr,
+        resolver: Ipv6Addr,
+        peer: SocketAddr,
+        forwarded_for: Option<IpAddr>,
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[derive(TryFromRowByIndex)]
+    struct Measurement {
+        exact: BigDecimal,
+        counted: BigDecimal,
+        approximate: BigDecimal,
+        optional_exact: Option<BigDecimal>,
+    }
+
+    #[cfg(feature = "semver")]
+    #[derive(TryFromRowByIndex)]
+    struct PluginRelease {
+        name: String,
+        version: semver::Version,
+        min_host_version: Option<Version>,
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(TryFromRowByIndex)]
+    struct Document {
+        metadata: serde_json::Value,
+        extra: Option<serde_json::Value>,
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct UserSettings {
+        theme: String,
+        notifications: bool,
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(TryFromRowByIndex)]
+    struct UserProfile {
+        #[turso(json)]
+        settings: UserSettings,
+        #[turso(json)]
+        previous_settings: Option<UserSettings>,
+        #[turso(json)]
+        tags: Vec<String>,
+    }
+
+    #[cfg(feature = "bytes")]
+    #[derive(TryFromRowByIndex)]
+    struct Payload {
+        body: Bytes,
+        signature: Option<Bytes>,
+    }
+
+    #[tokio::test]
+    async fn can_get_values_using_map() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+            .await?;
+
+        let rows = conn.query("SELECT id, name, value, image FROM customer;", ()).await?;
+
+        let customer_names = rows
+            .map_rows(|row| {
+
+
+
+                Ok(row
+                    .get_value(1)?
+                    .as_text()
+                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
+                    .clone())
+            })
+            .await?;
+
+        assert_eq!(customer_names.len(), 2);
+
+        assert_eq!(customer_names[0], "Charlie");
+        assert_eq!(customer_names[1], "Sarah");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn can_get_values_using_map_with_names() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            (),
+        )
+        .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+            .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+            .await?;
+
+        let mut statement = conn.prepare("SELECT id, name, value, image FROM customer;").await?;
+        let rows = statement.query(()).await?;
+
+        let column_indices = ColumnIndices::new(statement.columns());
+        let name_column_index = column_indices.get_index("name")?;
+
+        let customer_names = rows
+            .map_rows(|row| {
+                Ok(row
+                    .get_value(name_column_index)?
+                    .as_text()
+                    .ok_or_else(|| TursoMapperError::ConversionError("name is not a string".to_string()))?
+                    .clone())
+            })
+            .await?;
+
+        assert_eq!(customer_names.len(), 2);
+
+        assert_eq!(customer_names[0], "Charlie");
+        assert_eq!(customer_names[1], "Sarah");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn manual_try_from_row_impl_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(1),
+                Value::Text(Text::new("Charlie")),
+                Value::Float(3.12),
+                Value::Blob(vec![1, 2, 3]),
+            ]
+            .iter(),
+        );
+
+        let customer = CustomerWithManualTryFromRow::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 1);
+        assert_eq!(customer.name, "Charlie");
+        assert_eq!(customer.value, 3.12);
+        assert_eq!(customer.image, vec![1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn derive_macro_try_from_row_impl_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(1),
+                Value::Text(Text::new("Charlie")),
+                Value::Float(3.12),
+                Value::Blob(vec![1, 2, 3]),
+            ]
+            .iter(),
+        );
+
+        let customer = Customer::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 1);
+        assert_eq!(customer.name, "Charlie");
+        assert_eq!(customer.value, 3.12);
+        assert_eq!(customer.image, vec![1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_accepts_a_closure_keeping_state() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let mut mapped = 0;
+        let mut seen = std::collections::HashSet::new();
+        let first_sightings = conn
+            .query("SELECT 'ann' UNION ALL SELECT 'bob' UNION ALL SELECT 'ann';", ())
+            .await?
+            .map_rows(|row| {
+                mapped += 1;
+                let name = String::try_from_value(row.get_value(0)?)?;
+                Ok(seen.insert(name))
+            })
+            .await?;
+
+        assert_eq!(mapped, 3);
+        assert_eq!(first_sightings, [true, true, false]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_enumerate_passes_the_row_index() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 'ann' UNION ALL SELECT 'bob' UNION ALL SELECT NULL;";
+
+        let numbered = conn
+            .query("SELECT 'ann' UNION ALL SELECT 'bob';", ())
+            .await?
+            .map_rows_enumerate(|index, row| Ok(format!("{}. {}", index + 1, String::try_from_value(row.get_value(0)?)?)))
+            .await?;
+        assert_eq!(numbered, ["1. ann", "2. bob"]);
+
+        let result = conn
+            .query(sql, ())
+            .await?
+            .map_rows_enumerate(|_, row| String::try_from_value(row.get_value(0)?))
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar (row 2)"));
+
+        let result = conn.query(sql, ()).await?.map_rows(|row| String::try_from_value(row.get_value(0)?)).await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar (row 2)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_limited_fails_when_more_rows_are_left() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3;";
+
+        let ids = conn
+            .query(sql, ())
+            .await?
+            .map_rows_limited(3, |row| i64::try_from_value(row.get_value(0)?))
+            .await?;
+        assert_eq!(ids, [1, 2, 3]);
+
+        let mut mapped = 0;
+        let result = conn
+            .query(sql, ())
+            .await?
+            .map_rows_limited(2, |row| {
+                mapped += 1;
+                i64::try_from_value(row.get_value(0)?)
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::RowLimitExceeded { limit: 2 })));
+        // The row over the limit is never mapped
+        assert_eq!(mapped, 2);
+
+        let ids = conn
+            .query("SELECT 1 WHERE 0;", ())
+            .await?
+            .map_rows_limited(0, |row| i64::try_from_value(row.get_value(0)?))
+            .await?;
+        assert!(ids.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_on_a_mutable_reference_continues_from_the_cursor() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let mut rows = conn
+            .query("SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4 UNION ALL SELECT 5;", ())
+            .await?;
+        let id = |row: turso::Row| i64::try_from_value(row.get_value(0)?);
+
+        assert_eq!((&mut rows).map_rows_take(2, id).await?, [1, 2]);
+        assert_eq!((&mut rows).map_rows_take(2, id).await?, [3, 4]);
+        assert_eq!((&mut rows).map_rows_take(2, id).await?, [5]);
+        assert!((&mut rows).map_rows_take(2, id).await?.is_empty());
+
+        // A function borrowing the rows maps whatever is left of them
+        async fn sum_remaining(rows: &mut turso::Rows) -> TursoMapperResult<i64> {
+            rows.try_fold_rows(0, |sum, row| Ok(sum + i64::try_from_value(row.get_value(0)?)?)).await
+        }
+
+        let mut rows = conn.query("SELECT 10 UNION ALL SELECT 20 UNION ALL SELECT 30;", ()).await?;
+        assert_eq!((&mut rows).map_rows_take(1, id).await?, [10]);
+        assert_eq!(sum_remaining(&mut rows).await?, 50);
+        assert_eq!(rows.map_rows(id).await?, Vec::<i64>::new());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_collect_fills_the_collection_chosen_by_the_caller() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 3, 'cat' UNION ALL SELECT 1, 'ann' UNION ALL SELECT 2, 'bob' UNION ALL SELECT 1, 'amy';";
+
+        let names = conn
+            .query(sql, ())
+            .await?
+            .map_rows_collect::<HashSet<_>, _, _>(|row| String::try_from_value(row.get_value(1)?))
+            .await?;
+        assert_eq!(
+            names,
+            HashSet::from(["ann".to_string(), "amy".to_string(), "bob".to_string(), "cat".to_string()])
+        );
+
+        // Pairs collect into a map, where a later row replaces an earlier one with the same key
+        let names_by_id = conn
+            .query(sql, ())
+            .await?
+            .map_rows_collect::<BTreeMap<_, _>, _, _>(|row| Ok((i64::try_from_value(row.get_value(0)?)?, String::try_from_value(row.get_value(1)?)?)))
+            .await?;
+        assert_eq!(
+            names_by_id,
+            BTreeMap::from([(1, "amy".to_string()), (2, "bob".to_string()), (3, "cat".to_string())])
+        );
+
+        let result = conn
+            .query(sql, ())
+            .await?
+            .map_rows_collect::<HashSet<_>, _, _>(|row| String::try_from_value(row.get_value(0)?))
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scalar is not a string, got an integer (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_collect_maps_rows_into_any_collection() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 'rust' UNION ALL SELECT 'sql' UNION ALL SELECT 'rust' UNION ALL SELECT 'async';";
+
+        let distinct = conn.query_as_collect::<Tag, HashSet<_>>(sql, ()).await?;
+        assert_eq!(distinct.len(), 3);
+        assert!(distinct.contains(&Tag { name: "sql".to_string() }));
+
+        let sorted = conn.query_as_collect::<Tag, BTreeSet<_>>(sql, ()).await?;
+        assert_eq!(sorted.into_iter().map(|tag| tag.name).collect::<Vec<_>>(), ["async", "rust", "sql"]);
+
+        let all = conn.query_as_collect::<Tag, Vec<_>>(sql, ()).await?;
+        assert_eq!(all.len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_into_map_indexes_rows_by_key() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 1, 'ann' UNION ALL SELECT 2, 'bob' UNION ALL SELECT 1, 'amy';";
+        let name = |row: turso::Row| String::try_from_value(row.get_value(1)?);
+
+        let by_initial = conn
+            .query("SELECT 1, 'ann' UNION ALL SELECT 2, 'bob';", ())
+            .await?
+            .map_rows_into_map(|name: &String| name.chars().next(), name)
+            .await?;
+        assert_eq!(by_initial, HashMap::from([(Some('a'), "ann".to_string()), (Some('b'), "bob".to_string())]));
+
+        let result = conn.query(sql, ()).await?.map_rows_into_map(|name: &String| name.len(), name).await;
+        assert!(matches!(result, Err(TursoMapperError::DuplicateKey(msg)) if msg == "3 (row 1)"));
+
+        let by_length = conn.query(sql, ()).await?.map_rows_into_map_last_wins(|name: &String| name.len(), name).await?;
+        assert_eq!(by_length, HashMap::from([(3, "amy".to_string())]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_grouped_keeps_the_row_order_within_each_group() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 'ann' UNION ALL SELECT 'bob' UNION ALL SELECT 'amy' UNION ALL SELECT NULL;";
+        let name = |row: turso::Row| String::try_from_value(row.get_value(0)?);
+
+        let by_initial = conn
+            .query("SELECT 'ann' UNION ALL SELECT 'bob' UNION ALL SELECT 'amy' UNION ALL SELECT 'al';", ())
+            .await?
+            .map_rows_grouped(|name: &String| name.chars().next(), name)
+            .await?;
+        assert_eq!(by_initial.len(), 2);
+        assert_eq!(by_initial[&Some('a')], ["ann", "amy", "al"]);
+        assert_eq!(by_initial[&Some('b')], ["bob"]);
+
+        let result = conn.query(sql, ()).await?.map_rows_grouped(|name: &String| name.len(), name).await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar (row 3)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_names_the_row_of_a_missing_column() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        // Each row names the column holding its total, and the second row names one the query does not select
+        let mut statement = conn
+            .prepare("SELECT 'net' AS kind, 10 AS net_total, 12 AS gross_total UNION ALL SELECT 'tax', 3, 4;")
+            .await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let result = rows
+            .map_rows(|row| {
+                let kind = String::try_from_value(row.get_value(0)?)?;
+                let index = column_indices.get_index(&format!("{}_total", kind))?;
+                i64::try_from_value(row.get_value(index)?)
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "tax_total (row 1)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn filter_map_rows_keeps_only_the_rows_mapped_to_some() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        conn.execute("CREATE TABLE account (id INTEGER PRIMARY KEY, name TEXT, deleted INTEGER NOT NULL);", ())
+            .await?;
+        conn.execute(
+            "INSERT INTO account VALUES (1, 'ann', 0), (2, 'bob', 1), (3, 'cat', 0), (4, NULL, 1), (5, 'dan', 0);",
+            (),
+        )
+        .await?;
+
+        let live_accounts = |row: turso::Row| {
+            if bool::try_from_value(row.get_value(2)?)? {
+                return Ok(None);
+            }
+            Ok(Some(String::try_from_value(row.get_value(1)?)?))
+        };
+
+        let names = conn
+            .query("SELECT * FROM account ORDER BY id DESC;", ())
+            .await?
+            .filter_map_rows(live_accounts)
+            .await?;
+        assert_eq!(names, ["dan", "cat", "ann"]);
+
+        // A skipped row is still counted, and an error after rows were kept fails the whole call
+        let result = conn
+            .query("SELECT * FROM account ORDER BY id;", ())
+            .await?
+            .filter_map_rows(|row| Ok(Some(String::try_from_value(row.get_value(1)?)?)))
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar (row 3)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn try_fold_rows_folds_without_collecting_the_rows() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 2, 10.0 UNION ALL SELECT 3, 20.0 UNION ALL SELECT NULL, 5.0 UNION ALL SELECT 5, 1.0;";
+
+        // Weighted average of the second column, weighted by the first
+        let (weighted, weights) = conn
+            .query("SELECT 2, 10.0 UNION ALL SELECT 3, 20.0;", ())
+            .await?
+            .try_fold_rows((0.0, 0), |(weighted, weights), row| {
+                let weight = i64::try_from_value(row.get_value(0)?)?;
+                let value = f64::try_from_value(row.get_value(1)?)?;
+                Ok((weighted + weight as f64 * value, weights + weight))
+            })
+            .await?;
+        assert_eq!(weighted / weights as f64, 16.0);
+
+        let mut folded = 0;
+        let result = conn
+            .query(sql, ())
+            .await?
+            .try_fold_rows(0, |sum, row| {
+                folded += 1;
+                Ok(sum + i64::try_from_value(row.get_value(0)?)?)
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar (row 2)"));
+        // The row after the failing one is never folded
+        assert_eq!(folded, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn try_for_each_rows_stops_at_the_first_error() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let mut csv = String::new();
+        conn.query("SELECT 1, 'ann' UNION ALL SELECT 2, 'bob';", ())
+            .await?
+            .try_for_each_rows(|row| {
+                let id = i64::try_from_value(row.get_value(0)?)?;
+                let name = String::try_from_value(row.get_value(1)?)?;
+                csv.push_str(&format!("{},{}\n", id, name));
+                Ok(())
+            })
+            .await?;
+        assert_eq!(csv, "1,ann\n2,bob\n");
+
+        let mut seen = 0;
+        let result = conn
+            .query("SELECT 1 UNION ALL SELECT 'two' UNION ALL SELECT 3;", ())
+            .await?
+            .try_for_each_rows(|row| {
+                seen += 1;
+                i64::try_from_value(row.get_value(0)?)?;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scalar is not an integer or a real, got a string (row 1)"));
+        assert_eq!(seen, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_rows_async_awaits_each_row_in_order() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "SELECT 3 UNION ALL SELECT 1 UNION ALL SELECT 2;";
+
+        let ids = conn
+            .query(sql, ())
+            .await?
+            .map_rows_async(|row| async move {
+                tokio::time::sleep(Duration::ZERO).await;
+                i64::try_from_value(row.get_value(0)?)
+            })
+            .await?;
+        assert_eq!(ids, [3, 1, 2]);
+
+        let mut mapped = vec![];
+        let result = conn
+            .query(sql, ())
+            .await?
+            .map_rows_async(|row| {
+                let id = row.get_value(0).map_err(TursoMapperError::from).and_then(i64::try_from_value);
+                mapped.push(id.as_ref().ok().copied());
+                async move {
+                    tokio::time::sleep(Duration::ZERO).await;
+                    match id? {
+                        1 => Err(TursoMapperError::ConversionError("id 1 is not allowed".to_string())),
+                        id => Ok(id),
+                    }
+                }
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id 1 is not allowed (row 1)"));
+        // The row after the failing one is never mapped
+        assert_eq!(mapped, [Some(3), Some(1)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn end_to_end_test_with_map_rows_and_try_from_row() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            (),
+        )
+        .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+            .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+            .await?;
+
+        let customers = conn
+            .query("SELECT id, name, value, image FROM customer;", ())
+            .await?
+            .map_rows(Customer::try_from_row_by_index)
+            .await?;
+
+        assert_eq!(customers.len(), 2);
+
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].value, 3.12);
+        assert_eq!(customers[0].image, vec![0, 1, 2, 3]);
+
+        assert_eq!(customers[1].id, 2);
+        assert_eq!(customers[1].name, "Sarah");
+        assert_eq!(customers[1].value, 0.99);
+        assert_eq!(customers[1].image, vec![9, 8, 7, 6]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn end_to_end_test_with_query_as_by_index() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            (),
+        )
+        .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+            .await?;
+
+        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+            .await?;
+
+        let customers = conn.query_as_by_index::<Customer>("SELECT id, name, value, image FROM customer;", ()).await?;
+
+        assert_eq!(customers.len(), 2);
+
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].value, 3.12);
+        assert_eq!(customers[0].image, vec![0, 1, 2, 3]);
+
+        assert_eq!(customers[1].id, 2);
+        assert_eq!(customers[1].name, "Sarah");
+        assert_eq!(customers[1].value, 0.99);
+        assert_eq!(customers[1].image, vec![9, 8, 7, 6]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn option_types_support_works() -> TursoMapperResult<()> {
+        // Test with a manually created Row with some NULL values
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(1),
+                Value::Text(Text::new("Charlie")),
+                Value::Float(3.12),
+                Value::Null,
+                Value::Blob(vec![1, 2, 3]),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let customer = CustomerWithOptions::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 1);
+        assert_eq!(customer.name, "Charlie");
+        assert_eq!(customer.optional_value, Some(3.12));
+        assert_eq!(customer.optional_note, None);
+        assert_eq!(customer.optional_data, Some(vec![1, 2, 3]));
+        assert_eq!(customer.optional_count, None);
+
+        // Test with a Row with all non-NULL values
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(2),
+                Value::Text(Text::new("Sarah")),
+                Value::Float(0.99),
+                Value::Text(Text::new("Some note")),
+                Value::Blob(vec![9, 8, 7, 6]),
+                Value::Integer(42),
+            ]
+            .iter(),
+        );
+
+        let customer = CustomerWithOptions::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 2);
+        assert_eq!(customer.name, "Sarah");
+        assert_eq!(customer.optional_value, Some(0.99));
+        assert_eq!(customer.optional_note, Some("Some note".to_string()));
+        assert_eq!(customer.optional_data, Some(vec![9, 8, 7, 6]));
+        assert_eq!(customer.optional_count, Some(42));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bool_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(1), Value::Integer(0)].iter());
+
+        let customer = CustomerWithFlags::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 1);
+        assert!(customer.active);
+        assert_eq!(customer.verified, Some(false));
+
+        let row: Row = Row::from_iter([Value::Integer(2), Value::Integer(0), Value::Null].iter());
+
+        let customer = CustomerWithFlags::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 2);
+        assert!(!customer.active);
+        assert_eq!(customer.verified, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bool_support_rejects_invalid_values() {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(2), Value::Null].iter());
+        let result = CustomerWithFlags::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "active must be 0 or 1 to map to a bool, got 2"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(1), Value::Integer(-1)].iter());
+        let result = CustomerWithFlags::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "verified must be 0 or 1 to map to a bool, got -1"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Text(Text::new("true")), Value::Null].iter());
+        let result = CustomerWithFlags::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "active is not an integer"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null, Value::Null].iter());
+        let result = CustomerWithFlags::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "active"));
+    }
+
+    #[tokio::test]
+    async fn i32_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(i32::MAX as i64), Value::Integer(i32::MIN as i64)].iter());
+
+        let customer = CustomerWithSmallIds::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, i32::MAX);
+        assert_eq!(customer.parent_id, Some(i32::MIN));
+
+        let row: Row = Row::from_iter([Value::Integer(7), Value::Null].iter());
+
+        let customer = CustomerWithSmallIds::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id, 7);
+        assert_eq!(customer.parent_id, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn i32_support_rejects_out_of_range_values() {
+        let row: Row = Row::from_iter([Value::Integer(i32::MAX as i64 + 1), Value::Null].iter());
+        let result = CustomerWithSmallIds::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id value 2147483648 is out of range for i32"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(i32::MIN as i64 - 1)].iter());
+        let result = CustomerWithSmallIds::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "parent_id value -2147483649 is out of range for i32"));
+    }
+
+    #[tokio::test]
+    async fn i16_and_i8_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(-300), Value::Integer(127), Value::Integer(i16::MAX as i64), Value::Null].iter());
+
+        let codes = OrderStatusCodes::try_from_row_by_index(row)?;
+
+        assert_eq!(codes.status, -300);
+        assert_eq!(codes.priority, 127);
+        assert_eq!(codes.optional_status, Some(i16::MAX));
+        assert_eq!(codes.optional_priority, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn i16_and_i8_support_rejects_out_of_range_values() {
+        let row: Row = Row::from_iter([Value::Integer(40000), Value::Integer(1), Value::Null, Value::Null].iter());
+        let result = OrderStatusCodes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "status value 40000 is out of range for i16"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(1), Value::Null, Value::Integer(-129)].iter());
+        let result = OrderStatusCodes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional_priority value -129 is out of range for i8"));
+    }
+
+    #[tokio::test]
+    async fn unsigned_integer_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(8080),
+                Value::Integer(u32::MAX as i64),
+                Value::Integer(255),
+                Value::Null,
+                Value::Integer(0),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let stats = ServerStats::try_from_row_by_index(row)?;
+
+        assert_eq!(stats.port, 8080);
+        assert_eq!(stats.count, u32::MAX);
+        assert_eq!(stats.flags, 255);
+        assert_eq!(stats.optional_port, None);
+        assert_eq!(stats.optional_count, Some(0));
+        assert_eq!(stats.optional_flags, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsigned_integer_support_rejects_negative_and_out_of_range_values() {
+        let row: Row = Row::from_iter([Value::Integer(-1), Value::Integer(0), Value::Integer(0), Value::Null, Value::Null, Value::Null].iter());
+        let result = ServerStats::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "port value -1 is out of range for u16"));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(1),
+                Value::Integer(u32::MAX as i64 + 1),
+                Value::Integer(0),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = ServerStats::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "count value 4294967296 is out of range for u32"));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(1),
+                Value::Integer(0),
+                Value::Integer(0),
+                Value::Null,
+                Value::Null,
+                Value::Integer(256),
+            ]
+            .iter(),
+        );
+        let result = ServerStats::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional_flags value 256 is out of range for u8"));
+    }
+
+    #[tokio::test]
+    async fn u64_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(i64::MAX), Value::Null, Value::Integer(-1), Value::Integer(i64::MIN)].iter());
+
+        let hashes = ContentHashes::try_from_row_by_index(row)?;
+
+        assert_eq!(hashes.size, i64::MAX as u64);
+        assert_eq!(hashes.optional_size, None);
+        assert_eq!(hashes.hash, u64::MAX);
+        assert_eq!(hashes.optional_hash, Some(i64::MAX as u64 + 1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn u64_support_rejects_negative_values_without_bit_cast() {
+        let row: Row = Row::from_iter([Value::Integer(-1), Value::Null, Value::Integer(0), Value::Null].iter());
+        let result = ContentHashes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "size value -1 is out of range for u64"));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(i64::MIN), Value::Integer(0), Value::Null].iter());
+        let result = ContentHashes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional_size value -9223372036854775808 is out of range for u64"));
+    }
+
+    #[tokio::test]
+    async fn u64_bit_cast_round_trips_values_above_i64_max() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE content (size INTEGER NOT NULL, optional_size INTEGER, hash INTEGER NOT NULL, optional_hash INTEGER);",
+            (),
+        )
+        .await?;
+
+        let hash = u64::MAX - 5;
+        let optional_hash = i64::MAX as u64 + 1;
+
+        conn.execute(
+            "INSERT INTO content (size, optional_size, hash, optional_hash) VALUES (?, NULL, ?, ?);",
+            (42, hash as i64, optional_hash as i64),
+        )
+        .await?;
+
+        let hashes = conn
+            .query_as_by_index::<ContentHashes>("SELECT size, optional_size, hash, optional_hash FROM content;", ())
+            .await?;
+
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes[0].size, 42);
+        assert_eq!(hashes[0].optional_size, None);
+        assert_eq!(hashes[0].hash, hash);
+        assert_eq!(hashes[0].optional_hash, Some(optional_hash));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn f32_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Float(21.5), Value::Null, Value::Float(1013.25), Value::Float(f64::INFINITY)].iter());
+
+        let reading = SensorReading::try_from_row_by_index(row)?;
+
+        assert_eq!(reading.temperature, 21.5);
+        assert_eq!(reading.humidity, None);
+        assert_eq!(reading.pressure, 1013.25);
+        assert_eq!(reading.optional_pressure, Some(f32::INFINITY));
+
+        // Without strict_float the narrowing is a plain `as` cast
+        let row: Row = Row::from_iter([Value::Float(f64::MAX), Value::Float(0.1), Value::Float(0.0), Value::Null].iter());
+
+        let reading = SensorReading::try_from_row_by_index(row)?;
+
+        assert_eq!(reading.temperature, f32::INFINITY);
+        assert_eq!(reading.humidity, Some(0.1));
+        assert_eq!(reading.optional_pressure, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn f32_strict_float_rejects_out_of_range_values() {
+        let row: Row = Row::from_iter([Value::Float(0.0), Value::Null, Value::Float(1e300), Value::Null].iter());
+        let result = SensorReading::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("pressure value 1000") && msg.ends_with("is out of range for f32"))
+        );
+
+        let row: Row = Row::from_iter([Value::Float(0.0), Value::Null, Value::Float(0.0), Value::Float(-1e39)].iter());
+        let result = SensorReading::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("optional_pressure value -1000") && msg.ends_with("is out of range for f32"))
+        );
+    }
+
+    #[tokio::test]
+    async fn usize_and_isize_support_works() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE orders (name TEXT NOT NULL, amount INTEGER NOT NULL);", ()).await?;
+        conn.execute("INSERT INTO orders (name, amount) VALUES ('Charlie', 5), ('Charlie', -12), ('Sarah', 3);", ())
+            .await?;
+
+        let counts = conn
+            .query_as_by_index::<CustomerOrderCount>(
+                "SELECT name, count(*) AS n, sum(amount), NULL, min(amount) FROM orders GROUP BY name ORDER BY name;",
+                (),
+            )
+            .await?;
+
+        assert_eq!(counts.len(), 2);
+
+        assert_eq!(counts[0].name, "Charlie");
+        assert_eq!(counts[0].order_count, 2);
+        assert_eq!(counts[0].balance_delta, -7);
+        assert_eq!(counts[0].optional_order_count, None);
+        assert_eq!(counts[0].optional_balance_delta, Some(-12));
+
+        assert_eq!(counts[1].name, "Sarah");
+        assert_eq!(counts[1].order_count, 1);
+        assert_eq!(counts[1].balance_delta, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn usize_support_rejects_negative_values() {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("Charlie")),
+                Value::Integer(-1),
+                Value::Integer(0),
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = CustomerOrderCount::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "order_count value -1 is out of range for usize"));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("Charlie")),
+                Value::Integer(0),
+                Value::Integer(0),
+                Value::Integer(-5),
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = CustomerOrderCount::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional_order_count value -5 is out of range for usize"));
+    }
+
+    #[tokio::test]
+    async fn char_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("M")), Value::Text(Text::new("é"))].iter());
+
+        let codes = LegacyCodes::try_from_row_by_index(row)?;
+
+        assert_eq!(codes.code, 'M');
+        assert_eq!(codes.optional_code, Some('é'));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("X")), Value::Null].iter());
+
+        let codes = LegacyCodes::try_from_row_by_index(row)?;
+
+        assert_eq!(codes.code, 'X');
+        assert_eq!(codes.optional_code, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn char_support_rejects_empty_and_multi_character_strings() {
+        let row: Row = Row::from_iter([Value::Text(Text::new("")), Value::Null].iter());
+        let result = LegacyCodes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "code must contain exactly one character, got \"\""));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("M")), Value::Text(Text::new("MF"))].iter());
+        let result = LegacyCodes::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional_code must contain exactly one character, got \"MF\""));
+    }
+
+    #[tokio::test]
+    async fn non_zero_integer_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(42), Value::Integer(7), Value::Integer(-3)].iter());
+
+        let customer = CustomerWithNonZeroIds::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.id.get(), 1);
+        assert_eq!(customer.account_id.get(), 42);
+        assert_eq!(customer.region_id.get(), 7);
+        assert_eq!(customer.parent_id.map(NonZeroI64::get), Some(-3));
+
+        let row: Row = Row::from_iter([Value::Integer(2), Value::Integer(1), Value::Integer(1), Value::Null].iter());
+
+        let customer = CustomerWithNonZeroIds::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.parent_id, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn non_zero_integer_support_rejects_zero_and_out_of_range_values() {
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(1), Value::Integer(1), Value::Null].iter());
+        let result = CustomerWithNonZeroIds::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id must not be zero"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(1), Value::Integer(1), Value::Integer(0)].iter());
+        let result = CustomerWithNonZeroIds::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "parent_id must not be zero"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(-1), Value::Integer(1), Value::Null].iter());
+        let result = CustomerWithNonZeroIds::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "account_id value -1 is out of range for u32"));
+    }
+
+    #[tokio::test]
+    async fn boxed_and_shared_str_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("Charlie")),
+                Value::Text(Text::new("charlie@example.com")),
+                Value::Text(Text::new("Chaz")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let customer = SharedCustomer::try_from_row_by_index(row)?;
+
+        assert_eq!(&*customer.name, "Charlie");
+        assert_eq!(&*customer.email, "charlie@example.com");
+        assert_eq!(customer.nickname.as_deref(), Some("Chaz"));
+        assert_eq!(customer.note, None);
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Text(Text::new("")), Value::Null, Value::Null].iter());
+        let result = SharedCustomer::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "name is not a string"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cow_str_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("Charlie")),
+                Value::Text(Text::new("charlie@example.com")),
+                Value::Text(Text::new("Chaz")),
+            ]
+            .iter(),
+        );
+
+        let customer = CustomerWithCow::try_from_row_by_index(row)?;
+
+        assert!(matches!(customer.name, Cow::Owned(ref name) if name == "Charlie"));
+        assert_eq!(customer.email, "charlie@example.com");
+        assert_eq!(customer.nickname.as_deref(), Some("Chaz"));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("Sarah")), Value::Text(Text::new("sarah@example.com")), Value::Null].iter());
+
+        let customer = CustomerWithCow::try_from_row_by_index(row)?;
+
+        assert_eq!(customer.name, "Sarah");
+        assert_eq!(customer.nickname, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn boxed_slice_blob_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Blob(vec![1, 2, 3]), Value::Null].iter());
+
+        let attachment = Attachment::try_from_row_by_index(row)?;
+
+        assert_eq!(&*attachment.data, &[1, 2, 3]);
+        assert_eq!(attachment.thumbnail, None);
+
+        let row: Row = Row::from_iter([Value::Blob(vec![]), Value::Blob(vec![9])].iter());
+
+        let attachment = Attachment::try_from_row_by_index(row)?;
+
+        assert!(attachment.data.is_empty());
+        assert_eq!(attachment.thumbnail.as_deref(), Some(&[9][..]));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "bytes")]
+    #[tokio::test]
+    async fn bytes_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Blob(vec![1, 2, 3]), Value::Null].iter());
+
+        let payload = Payload::try_from_row_by_index(row)?;
+
+        assert_eq!(payload.body, Bytes::from_static(&[1, 2, 3]));
+        assert_eq!(payload.signature, None);
+
+        let row: Row = Row::from_iter([Value::Blob(vec![]), Value::Blob(vec![4, 5])].iter());
+
+        let payload = Payload::try_from_row_by_index(row)?;
+
+        assert!(payload.body.is_empty());
+        assert_eq!(payload.signature, Some(Bytes::from_static(&[4, 5])));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fixed_size_blob_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Blob(vec![7; 32]), Value::Blob((0..16).collect()), Value::Null].iter());
+
+        let key_material = KeyMaterial::try_from_row_by_index(row)?;
+
+        assert_eq!(key_material.hash, [7; 32]);
+        assert_eq!(key_material.key, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(key_material.previous_hash, None);
+
+        let row: Row = Row::from_iter([Value::Blob(vec![7; 32]), Value::Blob(vec![0; 16]), Value::Blob(vec![1; 32])].iter());
+
+        let key_material = KeyMaterial::try_from_row_by_index(row)?;
+
+        assert_eq!(key_material.previous_hash, Some([1; 32]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fixed_size_blob_support_rejects_wrong_lengths() {
+        let row: Row = Row::from_iter([Value::Blob(vec![7; 31]), Value::Blob(vec![0; 16]), Value::Null].iter());
+        let result = KeyMaterial::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "hash must be 32 bytes, got 31"));
+
+        let row: Row = Row::from_iter([Value::Blob(vec![7; 32]), Value::Blob(vec![0; 17]), Value::Null].iter());
+        let result = KeyMaterial::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "key must be 16 bytes, got 17"));
+
+        let row: Row = Row::from_iter([Value::Blob(vec![7; 32]), Value::Blob(vec![0; 16]), Value::Blob(vec![])].iter());
+        let result = KeyMaterial::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "previous_hash must be 32 bytes, got 0"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn chrono_datetime_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00")),
+                Value::Text(Text::new("2024-03-01 10:30:45")),
+                Value::Integer(1_709_289_045),
+                Value::Text(Text::new("2024-03-01 10:30:45.250")),
+            ]
+            .iter(),
+        );
+
+        let record = AuditedRecord::try_from_row_by_index(row)?;
+
+        let expected = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(10, 30, 45).unwrap();
+
+        assert_eq!(record.created_at, expected.and_utc());
+        assert_eq!(record.updated_at, expected);
+        assert_eq!(record.deleted_at, Some(expected.and_utc()));
+        assert_eq!(
+            record.archived_at,
+            Some(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_milli_opt(10, 30, 45, 250).unwrap())
+        );
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("2024-03-01 10:30:45")),
+                Value::Integer(1_709_289_045),
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let record = AuditedRecord::try_from_row_by_index(row)?;
+
+        assert_eq!(record.created_at, expected.and_utc());
+        assert_eq!(record.updated_at, expected);
+        assert_eq!(record.deleted_at, None);
+        assert_eq!(record.archived_at, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn chrono_datetime_support_rejects_malformed_text() {
+        let row: Row = Row::from_iter([Value::Text(Text::new("yesterday")), Value::Integer(0), Value::Null, Value::Null].iter());
+        let result = AuditedRecord::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "created_at could not be parsed as a date and time: \"yesterday\""));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(0), Value::Text(Text::new("2024-13-01 00:00:00")), Value::Null].iter());
+        let result = AuditedRecord::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "deleted_at could not be parsed as a date and time: \"2024-13-01 00:00:00\"")
+        );
+
+        let row: Row = Row::from_iter([Value::Float(1.5), Value::Integer(0), Value::Null, Value::Null].iter());
+        let result = AuditedRecord::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "created_at is not a string or an integer"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn chrono_naive_date_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("2024-12-25")), Value::Text(Text::new("2024-12-27"))].iter());
+
+        let holiday = Holiday::try_from_row_by_index(row)?;
+
+        assert_eq!(holiday.date, NaiveDate::from_ymd_opt(2024, 12, 25).unwrap());
+        assert_eq!(holiday.observed_on, NaiveDate::from_ymd_opt(2024, 12, 27));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("2025-01-01")), Value::Null].iter());
+
+        let holiday = Holiday::try_from_row_by_index(row)?;
+
+        assert_eq!(holiday.observed_on, None);
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("25/12/2024")), Value::Null].iter());
+        let result = Holiday::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "date could not be parsed as a date: \"25/12/2024\""));
+
+        let row: Row = Row::from_iter([Value::Integer(20241225), Value::Null].iter());
+        let result = Holiday::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "date is not a string"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn chrono_naive_time_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("09:00")),
+                Value::Text(Text::new("17:30:15")),
+                Value::Text(Text::new("16:45:30.125")),
+            ]
+            .iter(),
+        );
+
+        let hours = OpeningHours::try_from_row_by_index(row)?;
+
+        assert_eq!(hours.opens_at, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(hours.closes_at, NaiveTime::from_hms_opt(17, 30, 15).unwrap());
+        assert_eq!(hours.last_entry_at, NaiveTime::from_hms_milli_opt(16, 45, 30, 125));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("09:00")), Value::Text(Text::new("17:00")), Value::Null].iter());
+
+        let hours = OpeningHours::try_from_row_by_index(row)?;
+
+        assert_eq!(hours.last_entry_at, None);
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("9am")), Value::Text(Text::new("17:00")), Value::Null].iter());
+        let result = OpeningHours::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "opens_at could not be parsed as a time: \"9am\""));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("09:00")), Value::Text(Text::new("25:00")), Value::Null].iter());
+        let result = OpeningHours::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "closes_at could not be parsed as a time: \"25:00\""));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "time")]
+    #[tokio::test]
+    async fn time_datetime_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00")),
+                Value::Text(Text::new("2024-03-01 10:30:45")),
+                Value::Integer(1_709_289_045),
+                Value::Text(Text::new("2024-03-01 10:30:45.5")),
+            ]
+            .iter(),
+        );
+
+        let job = ScheduledJob::try_from_row_by_index(row)?;
+
+        assert_eq!(job.scheduled_at, datetime!(2024-03-01 10:30:45 UTC));
+        assert_eq!(job.local_run_at, datetime!(2024-03-01 10:30:45));
+        assert_eq!(job.finished_at, Some(datetime!(2024-03-01 10:30:45 UTC)));
+        assert_eq!(job.local_finished_at, Some(datetime!(2024-03-01 10:30:45.5)));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Text(Text::new("2024-03-01 10:30:45")), Value::Null, Value::Null].iter());
+
+        let job = ScheduledJob::try_from_row_by_index(row)?;
+
+        assert_eq!(job.scheduled_at, OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(job.finished_at, None);
+        assert_eq!(job.local_finished_at, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "time")]
+    #[tokio::test]
+    async fn time_datetime_support_rejects_malformed_values() {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("soon")),
+                Value::Text(Text::new("2024-03-01 10:30:45")),
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = ScheduledJob::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scheduled_at could not be parsed as a date and time: \"soon\""));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(0), Value::Null, Value::Null].iter());
+        let result = ScheduledJob::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "local_run_at is not a string"));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(i64::MAX),
+                Value::Text(Text::new("2024-03-01 10:30:45")),
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = ScheduledJob::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scheduled_at value 9223372036854775807 is out of range for a unix timestamp")
+        );
+    }
+
+    #[cfg(feature = "jiff")]
+    #[tokio::test]
+    async fn jiff_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00")),
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00[+02:00]")),
+                Value::Integer(1_709_289_045),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let meeting = Meeting::try_from_row_by_index(row)?;
+
+        let expected = Timestamp::from_second(1_709_289_045).unwrap();
+
+        assert_eq!(meeting.starts_at, expected);
+        assert_eq!(meeting.local_start.timestamp(), expected);
+        assert_eq!(meeting.local_start.offset().seconds(), 2 * 60 * 60);
+        assert_eq!(meeting.ended_at, Some(expected));
+        assert_eq!(meeting.local_end, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "jiff")]
+    #[tokio::test]
+    async fn jiff_support_rejects_malformed_values() {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("tomorrow")),
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00[+02:00]")),
+                Value::Null,
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = Meeting::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("starts_at could not be parsed as a timestamp: \"tomorrow\"")));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(0), Value::Null, Value::Null].iter());
+        let result = Meeting::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "local_start is not a string"));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(0),
+                Value::Text(Text::new("2024-03-01T12:30:45+02:00[+02:00]")),
+                Value::Null,
+                Value::Text(Text::new("later")),
+            ]
+            .iter(),
+        );
+        let result = Meeting::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("local_end could not be parsed as a zoned date and time: \"later\""))
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[tokio::test]
+    async fn uuid_support_works() -> TursoMapperResult<()> {
+        let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Blob(id.as_bytes().to_vec()),
+                Value::Text(Text::new("67e55044-10b1-426f-9247-bb680e5fe0c8")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let session = Session::try_from_row_by_index(row)?;
+
+        assert_eq!(session.id, id);
+        assert_eq!(session.legacy_id, id);
+        assert_eq!(session.parent_id, None);
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("67e5504410b1426f9247bb680e5fe0c8")),
+                Value::Blob(id.as_bytes().to_vec()),
+                Value::Blob(Uuid::nil().as_bytes().to_vec()),
+            ]
+            .iter(),
+        );
+
+        let session = Session::try_from_row_by_index(row)?;
+
+        assert_eq!(session.id, id);
+        assert_eq!(session.legacy_id, id);
+        assert_eq!(session.parent_id, Some(Uuid::nil()));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "uuid")]
+    #[tokio::test]
+    async fn uuid_support_rejects_malformed_values() {
+        let row: Row = Row::from_iter([Value::Blob(vec![1, 2, 3]), Value::Blob(vec![0; 16]), Value::Null].iter());
+        let result = Session::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id must be 16 bytes to map to a uuid, got 3"));
+
+        let row: Row = Row::from_iter([Value::Blob(vec![0; 16]), Value::Blob(vec![0; 16]), Value::Text(Text::new("not-a-uuid"))].iter());
+        let result = Session::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "parent_id could not be parsed as a uuid: \"not-a-uuid\""));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Blob(vec![0; 16]), Value::Null].iter());
+        let result = Session::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id is not a blob or a string"));
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[tokio::test]
+    async fn big_decimal_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("123456789012345678901234567890.000000000000000000001")),
+                Value::Integer(42),
+                Value::Float(0.5),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let measurement = Measurement::try_from_row_by_index(row)?;
+
+        assert_eq!(
+            measurement.exact,
+            "123456789012345678901234567890.000000000000000000001".parse::<BigDecimal>().unwrap()
+        );
+        assert_eq!(measurement.counted, BigDecimal::from(42));
+        assert_eq!(measurement.approximate, "0.5".parse::<BigDecimal>().unwrap());
+        assert_eq!(measurement.optional_exact, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[tokio::test]
+    async fn big_decimal_support_rejects_malformed_values() {
+        let row: Row = Row::from_iter([Value::Text(Text::new("1.2.3")), Value::Integer(0), Value::Float(0.0), Value::Null].iter());
+        let result = Measurement::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "exact could not be parsed as a decimal: \"1.2.3\""));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(0), Value::Float(f64::NAN), Value::Null].iter());
+        let result = Measurement::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "approximate value NaN cannot be represented as a decimal"));
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn json_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new(r#"{"tags":["a","b"],"count":2}"#)), Value::Null].iter());
+        let document = Document::try_from_row_by_index(row)?;
+        assert_eq!(document.metadata, serde_json::json!({"tags": ["a", "b"], "count": 2}));
+        assert_eq!(document.extra, None);
+
+        let row: Row = Row::from_iter([Value::Blob(br#"[1,2,3]"#.to_vec()), Value::Text(Text::new("null"))].iter());
+        let document = Document::try_from_row_by_index(row)?;
+        assert_eq!(document.metadata, serde_json::json!([1, 2, 3]));
+        assert_eq!(document.extra, Some(serde_json::Value::Null));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn json_support_rejects_malformed_json() {
+        let row: Row = Row::from_iter([Value::Text(Text::new("{not json")), Value::Null].iter());
+        let result = Document::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("metadata could not be parsed as JSON: ")));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("{}")), Value::Text(Text::new("[1,"))].iter());
+        let result = Document::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("extra could not be parsed as JSON: ")));
+
+        let row: Row = Row::from_iter([Value::Blob(vec![0xff, 0xfe]), Value::Null].iter());
+        let result = Document::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("metadata could not be parsed as JSON: ")));
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn json_attribute_deserializes_typed_fields() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new(r#"{"theme":"dark","notifications":true}"#)),
+                Value::Null,
+                Value::Blob(br#"["vip","beta"]"#.to_vec()),
+            ]
+            .iter(),
+        );
+        let profile = UserProfile::try_from_row_by_index(row)?;
+        assert_eq!(
+            profile.settings,
+            UserSettings {
+                theme: "dark".to_string(),
+                notifications: true
+            }
+        );
+        assert_eq!(profile.previous_settings, None);
+        assert_eq!(profile.tags, vec!["vip".to_string(), "beta".to_string()]);
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new(r#"{"theme":"dark","notifications":true}"#)),
+                Value::Text(Text::new(r#"{"theme":"light"}"#)),
+                Value::Text(Text::new("[]")),
+            ]
+            .iter(),
+        );
+        let result = UserProfile::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("previous_settings could not be parsed as JSON: missing field `notifications`"))
+        );
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("{not json")), Value::Null, Value::Text(Text::new("[]"))].iter());
+        let result = UserProfile::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("settings could not be parsed as JSON: ")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn network_address_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("2001:db8::1")),
+                Value::Text(Text::new("192.168.0.1")),
+                Value::Text(Text::new("::1")),
+                Value::Text(Text::new("10.0.0.5:8080")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+
+        let entry = AuditEntry::try_from_row_by_index(row)?;
+
+        assert_eq!(entry.client_ip, "2001:db8::1".parse::<IpAddr>().unwrap());
+        assert_eq!(entry.gateway, Ipv4Addr::new(192, 168, 0, 1));
+        assert_eq!(entry.resolver, Ipv6Addr::LOCALHOST);
+        assert_eq!(entry.peer, "10.0.0.5:8080".parse::<SocketAddr>().unwrap());
+        assert_eq!(entry.forwarded_for, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn network_address_support_rejects_invalid_values() {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("not-an-ip")),
+                Value::Text(Text::new("192.168.0.1")),
+                Value::Text(Text::new("::1")),
+                Value::Text(Text::new("10.0.0.5:8080")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = AuditEntry::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "client_ip could not be parsed as an IP address: \"not-an-ip\""));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("127.0.0.1")),
+                Value::Text(Text::new("192.168.0.1")),
+                Value::Text(Text::new("::1")),
+                Value::Text(Text::new("10.0.0.5")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = AuditEntry::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "peer could not be parsed as a socket address: \"10.0.0.5\""));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(2130706433),
+                Value::Text(Text::new("192.168.0.1")),
+                Value::Text(Text::new("::1")),
+                Value::Text(Text::new("10.0.0.5:8080")),
+                Value::Null,
+            ]
+            .iter(),
+        );
+        let result = AuditEntry::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "client_ip is not a string"));
+    }
+
+    #[tokio::test]
+    async fn path_buf_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("/var/data/report.csv")), Value::Text(Text::new("backups/report.csv"))].iter());
+        let file = StoredFile::try_from_row_by_index(row)?;
+        assert_eq!(file.path, PathBuf::from("/var/data/report.csv"));
+        assert_eq!(file.backup_path, Some(PathBuf::from("backups/report.csv")));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("report.csv")), Value::Null].iter());
+        let file = StoredFile::try_from_row_by_index(row)?;
+        assert_eq!(file.backup_path, None);
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null].iter());
+        let result = StoredFile::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "path is not a string"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "semver")]
+    #[tokio::test]
+    async fn semver_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("exporter")), Value::Text(Text::new("1.4.0-beta.2")), Value::Null].iter());
+        let release = PluginRelease::try_from_row_by_index(row)?;
+        assert_eq!(release.name, "exporter");
+        assert_eq!(release.version, Version::parse("1.4.0-beta.2").unwrap());
+        assert_eq!(release.min_host_version, None);
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("exporter")),
+                Value::Text(Text::new("2.0.0")),
+                Value::Text(Text::new("0.9.1")),
+            ]
+            .iter(),
+        );
+        let release = PluginRelease::try_from_row_by_index(row)?;
+        assert_eq!(release.min_host_version, Some(Version::new(0, 9, 1)));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "semver")]
+    #[tokio::test]
+    async fn semver_support_rejects_invalid_versions() {
+        let row: Row = Row::from_iter([Value::Text(Text::new("exporter")), Value::Text(Text::new("1.4")), Value::Null].iter());
+        let result = PluginRelease::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("version could not be parsed as a version: \"1.4\"")));
+
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("exporter")),
+                Value::Text(Text::new("1.0.0")),
+                Value::Text(Text::new("latest")),
+            ]
+            .iter(),
+        );
+        let result = PluginRelease::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("min_host_version could not be parsed as a version: \"latest\""))
+        );
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("exporter")), Value::Null, Value::Null].iter());
+        let result = PluginRelease::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(field)) if field == "version"));
+    }
+
+    #[tokio::test]
+    async fn text_enum_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Text(Text::new("on_hold")), Value::Text(Text::new("pending"))].iter());
+        let order = OrderWithStatus::try_from_row_by_index(row)?;
+        assert_eq!(order.id, 1);
+        assert_eq!(order.status, OrderStatus::OnHold);
+        assert_eq!(order.previous_status, Some(OrderStatus::Pending));
+
+        let row: Row = Row::from_iter([Value::Integer(2), Value::Text(Text::new("canceled")), Value::Null].iter());
+        let order = OrderWithStatus::try_from_row_by_index(row)?;
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.previous_status, None);
+
+        assert_eq!(OrderStatus::try_from_value(turso::Value::Text("shipped".to_string()))?, OrderStatus::Shipped);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn text_enum_support_rejects_unknown_values() {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Text(Text::new("lost")), Value::Null].iter());
+        let result = OrderWithStatus::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg))
+            if msg == "status: \"lost\" is not a valid OrderStatus, expected one of \"pending\", \"shipped\", \"on_hold\", \"canceled\""));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Text(Text::new("pending")), Value::Text(Text::new("Cancelled"))].iter());
+        let result = OrderWithStatus::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("previous_status: \"Cancelled\" is not a valid OrderStatus")));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Integer(0), Value::Null].iter());
+        let result = OrderWithStatus::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "status: expected a string to map to OrderStatus, got an integer"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null, Value::Null].iter());
+        let result = OrderWithStatus::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(field)) if field == "status"));
+    }
+
+    #[tokio::test]
+    async fn integer_enum_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(5), Value::Null, Value::Integer(-1)].iter());
+        let ticket = Ticket::try_from_row_by_index(row)?;
+        assert_eq!(ticket.priority, Priority::Urgent);
+        assert_eq!(ticket.escalated_priority, None);
+        assert_eq!(ticket.visibility, Visibility::Hidden);
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(1), Value::Integer(10)].iter());
+        let ticket = Ticket::try_from_row_by_index(row)?;
+        assert_eq!(ticket.priority, Priority::Low);
+        assert_eq!(ticket.escalated_priority, Some(Priority::Normal));
+        assert_eq!(ticket.visibility, Visibility::Public);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn integer_enum_support_rejects_unknown_values() {
+        let row: Row = Row::from_iter([Value::Integer(3), Value::Null, Value::Integer(10)].iter());
+        let result = Ticket::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "priority: 3 is not a valid Priority, expected one of 0, 1, 5"));
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Integer(2), Value::Integer(10)].iter());
+        let result = Ticket::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "escalated_priority: 2 is not a valid Priority, expected one of 0, 1, 5")
+        );
+
+        let row: Row = Row::from_iter([Value::Integer(0), Value::Null, Value::Integer(0)].iter());
+        let result = Ticket::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "visibility: 0 is not a valid Visibility, expected one of -1, 10"));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("low")), Value::Null, Value::Integer(10)].iter());
+        let result = Ticket::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "priority: expected an integer to map to Priority, got a string"));
+    }
+
+    #[tokio::test]
+    async fn enum_other_variant_absorbs_unknown_values() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("sms")), Value::Text(Text::new("pigeon")), Value::Integer(7)].iter());
+        let notification = Notification::try_from_row_by_index(row)?;
+        assert_eq!(notification.channel, DeliveryChannel::Sms);
+        assert_eq!(notification.fallback_channel, Some(DeliveryChannel::Unknown("pigeon".to_string())));
+        assert_eq!(notification.region, Region::Unassigned);
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("email")), Value::Null, Value::Integer(2)].iter());
+        let notification = Notification::try_from_row_by_index(row)?;
+        assert_eq!(notification.channel, DeliveryChannel::Email);
+        assert_eq!(notification.fallback_channel, None);
+        assert_eq!(notification.region, Region::Americas);
+
+        // Values of the wrong type are still rejected rather than absorbed
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null, Value::Integer(1)].iter());
+        let result = Notification::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "channel: expected a string to map to DeliveryChannel, got an integer"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn newtype_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(42), Value::Text(Text::new("ann@example.com")), Value::Null, Value::Integer(1200)].iter());
+        let contact = CustomerContact::try_from_row_by_index(row)?;
+        assert_eq!(contact.customer_id, CustomerId(42));
+        assert_eq!(contact.email, Email("ann@example.com".to_string()));
+        assert_eq!(contact.backup_email, None);
+        assert_eq!(contact.points, LoyaltyPoints(1200));
+
+        assert_eq!(
+            Email::try_from_value(turso::Value::Text("bob@example.com".to_string()))?,
+            Email("bob@example.com".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn newtype_support_reports_inner_conversion_errors() {
+        let row: Row = Row::from_iter(
+            [
+                Value::Text(Text::new("42")),
+                Value::Text(Text::new("ann@example.com")),
+                Value::Null,
+                Value::Integer(0),
+            ]
+            .iter(),
+        );
+        let result = CustomerContact::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "customer_id: CustomerId is not an integer or a real"));
+
+        let row: Row = Row::from_iter([Value::Integer(42), Value::Text(Text::new("ann@example.com")), Value::Null, Value::Integer(-5)].iter());
+        let result = CustomerContact::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "points: LoyaltyPoints value -5 is out of range for u32"));
+
+        let row: Row = Row::from_iter([Value::Integer(42), Value::Null, Value::Null, Value::Integer(0)].iter());
+        let result = CustomerContact::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(field)) if field == "email"));
+
+        let result = CustomerId::try_from_value(turso::Value::Null);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(field)) if field == "CustomerId"));
+    }
+
+    #[test]
+    fn generic_newtype_support_works() -> TursoMapperResult<()> {
+        assert_eq!(Audited::<CustomerId>::try_from_value(turso::Value::Integer(42))?, Audited(CustomerId(42)));
+        assert_eq!(Digest::<2>::try_from_value(turso::Value::Blob(vec![1, 2]))?, Digest([1, 2]));
+
+        let result = Digest::<4>::try_from_value(turso::Value::Blob(vec![1, 2]));
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "Digest must be 4 bytes, got 2"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tuple_struct_support_works() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT, x REAL NOT NULL, y REAL NOT NULL);",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO customer (name, x, y) VALUES ('Charlie', 1.5, -2.0), (NULL, 0.0, 3.25);", ())
+            .await?;
+
+        let points = conn.query_as_by_index::<Point>("SELECT x, y FROM customer ORDER BY id;", ()).await?;
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].0, points[0].1), (1.5, -2.0));
+        assert_eq!((points[1].0, points[1].1), (0.0, 3.25));
+
+        let ids_and_names = conn.query_as_by_index::<IdAndName>("SELECT id, name FROM customer ORDER BY id;", ()).await?;
+        assert_eq!((ids_and_names[0].0, ids_and_names[0].1.as_deref()), (1, Some("Charlie")));
+        assert_eq!((ids_and_names[1].0, ids_and_names[1].1.as_deref()), (2, None));
+
+        let row: Row = Row::from_iter([Value::Float(1.0), Value::Text(Text::new("2.0"))].iter());
+        let result = Point::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "field 1 is not a real or an integer"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn raw_identifier_fields_are_named_without_prefix() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Text(Text::new("keyword")), Value::Integer(3)].iter());
+        let token = Token::try_from_row_by_index(row)?;
+        assert_eq!(token.r#type, "keyword");
+        assert_eq!(token.r#match, Some(3));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null].iter());
+        let result = Token::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "type is not a string"));
+
+        let row: Row = Row::from_iter([Value::Null, Value::Null].iter());
+        let result = Token::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::NullValue(field)) if field == "type"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fully_qualified_option_fields_map_null_to_none() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null, Value::Null].iter());
+        let customer = CustomerWithQualifiedOptions::try_from_row_by_index(row)?;
+        assert_eq!(customer.id, 1);
+        assert_eq!(customer.description, None);
+        assert_eq!(customer.referrer_id, None);
+
+        let row: Row = Row::from_iter([Value::Integer(2), Value::Text(Text::new("Returning")), Value::Integer(1)].iter());
+        let customer = CustomerWithQualifiedOptions::try_from_row_by_index(row)?;
+        assert_eq!(customer.description, Some("Returning".to_string()));
+        assert_eq!(customer.referrer_id, Some(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fully_qualified_field_types_are_supported() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Integer(7),
+                Value::Text(Text::new("Charlie")),
+                Value::Text(Text::new("Chaz")),
                 Value::Blob(vec![1, 2, 3]),
+                Value::Blob(vec![4]),
+                Value::Text(Text::new("Regular")),
+                Value::Text(Text::new("/home/charlie")),
             ]
             .iter(),
         );
+        let customer = CustomerWithQualifiedTypes::try_from_row_by_index(row)?;
+        assert_eq!(customer.id, 7);
+        assert_eq!(customer.name, "Charlie");
+        assert_eq!(customer.nickname, "Chaz");
+        assert_eq!(customer.image, vec![1, 2, 3]);
+        assert_eq!(customer.thumbnail.as_deref(), Some(&[4u8][..]));
+        assert_eq!(customer.description, "Regular");
+        assert_eq!(customer.home, PathBuf::from("/home/charlie"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn duration_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Integer(30), Value::Integer(250), Value::Null, Value::Integer(100)].iter());
+        let policy = RetryPolicy::try_from_row_by_index(row)?;
+        assert_eq!(policy.timeout, Duration::from_secs(30));
+        assert_eq!(policy.backoff, Duration::from_millis(250));
+        assert_eq!(policy.jitter, None);
+        assert_eq!(policy.resolution, Duration::from_nanos(100));
+
+        // A REAL is a fractional number of the field's unit, so seconds unless set otherwise
+        let row: Row = Row::from_iter([Value::Float(1.5), Value::Float(0.5), Value::Integer(20), Value::Integer(0)].iter());
+        let policy = RetryPolicy::try_from_row_by_index(row)?;
+        assert_eq!(policy.timeout, Duration::from_millis(1500));
+        assert_eq!(policy.backoff, Duration::from_micros(500));
+        assert_eq!(policy.jitter, Some(Duration::from_micros(20)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn duration_support_rejects_negative_values() {
+        let row: Row = Row::from_iter([Value::Integer(-1), Value::Integer(0), Value::Null, Value::Integer(0)].iter());
+        let result = RetryPolicy::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "timeout must not be negative, got -1"));
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Float(-0.5), Value::Null, Value::Integer(0)].iter());
+        let result = RetryPolicy::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "backoff must not be negative, got -0.5"));
+
+        let row: Row = Row::from_iter([Value::Float(-1.5), Value::Integer(0), Value::Null, Value::Integer(0)].iter());
+        let result = RetryPolicy::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "timeout must not be negative, got -1.5"));
+
+        let row: Row = Row::from_iter([Value::Float(1e30), Value::Integer(0), Value::Null, Value::Integer(0)].iter());
+        let result = RetryPolicy::try_from_row_by_index(row);
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "timeout value 1000000000000000000000000000000 is out of range for a duration")
+        );
+    }
+
+    #[tokio::test]
+    async fn text_from_blob_support_works() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter(
+            [
+                Value::Blob("Héllo".as_bytes().to_vec()),
+                Value::Blob(b"body".to_vec()),
+                Value::Text(Text::new("ann")),
+            ]
+            .iter(),
+        );
+        let note = LegacyNote::try_from_row_by_index(row)?;
+        assert_eq!(note.title, "Héllo");
+        assert_eq!(note.body, Some("body".to_string()));
+        assert_eq!(note.author, "ann");
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("plain")), Value::Null, Value::Text(Text::new("bob"))].iter());
+        let note = LegacyNote::try_from_row_by_index(row)?;
+        assert_eq!(note.title, "plain");
+        assert_eq!(note.body, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn text_from_blob_support_rejects_invalid_utf8() {
+        let row: Row = Row::from_iter([Value::Blob(vec![0x66, 0xff]), Value::Null, Value::Text(Text::new("ann"))].iter());
+        let result = LegacyNote::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("title is not valid UTF-8: ")));
+
+        // Fields without the attribute keep rejecting blobs
+        let row: Row = Row::from_iter([Value::Text(Text::new("t")), Value::Null, Value::Blob(b"ann".to_vec())].iter());
+        let result = LegacyNote::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "author is not a string"));
+    }
+
+    #[tokio::test]
+    async fn lossy_text_attribute_replaces_invalid_utf8() -> TursoMapperResult<()> {
+        let row: Row = Row::from_iter([Value::Blob(vec![0x66, 0xff, 0x6f]), Value::Blob("Héllo".as_bytes().to_vec())].iter());
+        let note = ImportedNote::try_from_row_by_index(row)?;
+        assert_eq!(note.title, "f\u{FFFD}o");
+        assert_eq!(note.body, Some("Héllo".to_string()));
+
+        let row: Row = Row::from_iter([Value::Text(Text::new("plain")), Value::Null].iter());
+        let note = ImportedNote::try_from_row_by_index(row)?;
+        assert_eq!(note.title, "plain");
+        assert_eq!(note.body, None);
+
+        let row: Row = Row::from_iter([Value::Integer(1), Value::Null].iter());
+        let result = ImportedNote::try_from_row_by_index(row);
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "title is not a string or a blob"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rename_attribute_maps_fields_by_column_name() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, description) VALUES ('Charlie', NULL);", ()).await?;
+
+        // The columns are selected in a different order to the struct fields
+        let mut statement = conn.prepare("SELECT description, name, id FROM customer;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+
+        let customers = rows.map_rows(|row| CustomerSummary::try_from_row(row, &column_indices)).await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].customer_name, "Charlie");
+        assert_eq!(customers[0].display_name, "Charlie");
+        assert_eq!(customers[0].description, None);
+
+        // By index mapping ignores the rename
+        let customers = conn
+            .query_as_by_index::<CustomerSummary>("SELECT id, name, 'Chaz', description FROM customer;", ())
+            .await?;
+        assert_eq!(customers[0].display_name, "Chaz");
+
+        let mut statement = conn.prepare("SELECT id, description FROM customer;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let result = rows.map_rows(|row| CustomerSummary::try_from_row(row, &column_indices)).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "name (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_by_name_maps_every_row_with_the_same_column_indices() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute(
+            "INSERT INTO customer (name, description) VALUES ('Charlie', NULL), ('Sarah', 'Regular'), ('Ann', 'New'), ('Bob', NULL);",
+            (),
+        )
+        .await?;
+
+        let customers = conn
+            .query_as_by_name::<CustomerSummary>("SELECT id, description, name FROM customer ORDER BY name;", ())
+            .await?;
+        let summaries = customers
+            .iter()
+            .map(|customer| (customer.id, customer.customer_name.as_str(), customer.description.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summaries,
+            [(3, "Ann", Some("New")), (4, "Bob", None), (1, "Charlie", None), (2, "Sarah", Some("Regular"))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_by_name_maps_columns_in_any_order() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, description) VALUES ('Charlie', NULL), ('Sarah', 'Regular');", ())
+            .await?;
+
+        // The columns are selected in a different order to the struct fields
+        let customers = conn
+            .query_as_by_name::<CustomerSummary>("SELECT description, name, id FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers.len(), 2);
+        assert_eq!(customers[1].id, 2);
+        assert_eq!(customers[1].customer_name, "Sarah");
+        assert_eq!(customers[1].display_name, "Sarah");
+        assert_eq!(customers[1].description.as_deref(), Some("Regular"));
+
+        let customers = conn
+            .query_as_by_name::<CustomerSummary>("SELECT name, id, description FROM customer WHERE id = ?;", [1])
+            .await?;
+        assert_eq!(customers[0].customer_name, "Charlie");
+        assert_eq!(customers[0].description, None);
+
+        let result = conn.query_as_by_name::<CustomerSummary>("SELECT id, description FROM customer;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "name (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn index_attribute_overrides_field_position() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, description) VALUES ('Charlie', 'Regular');", ())
+            .await?;
+
+        let customers = conn
+            .query_as_by_index::<CustomerWithIdLast>("SELECT name, description, id FROM customer;", ())
+            .await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].description, Some("Regular".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn skipped_fields_do_not_take_a_column() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, description) VALUES ('Charlie', 'Regular');", ())
+            .await?;
+
+        let customers = conn
+            .query_as_by_index::<CustomerWithComputedFields>("SELECT id, name, description FROM customer;", ())
+            .await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].display_name_cache, None);
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].source, "unknown");
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].marker, std::marker::PhantomData);
+        assert_eq!(customers[0].description, Some("Regular".to_string()));
+
+        let mut statement = conn.prepare("SELECT description, name, id FROM customer;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let customers = rows.map_rows(|row| CustomerWithComputedFields::try_from_row(row, &column_indices)).await?;
+
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].source, "unknown");
+        assert_eq!(customers[0].name, "Charlie");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn default_attribute_replaces_null_and_missing_columns() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT, credit_limit INTEGER);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, credit_limit) VALUES (NULL, NULL);", ()).await?;
+        conn.execute("INSERT INTO customer (name, credit_limit) VALUES ('Charlie', 50);", ()).await?;
+
+        let customers = conn
+            .query_as_by_index::<CustomerWithDefaults>("SELECT id, name, credit_limit FROM customer ORDER BY id;", ())
+            .await?;
+
+        assert_eq!(customers.len(), 2);
+        assert_eq!(customers[0].name, "");
+        assert_eq!(customers[0].credit_limit, 1000);
+        assert_eq!(customers[1].name, "Charlie");
+        assert_eq!(customers[1].credit_limit, 50);
+
+        // A column missing from a by-name mapping also gives the default
+        let mut statement = conn.prepare("SELECT id, name FROM customer ORDER BY id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let customers = rows.map_rows(|row| CustomerWithDefaults::try_from_row(row, &column_indices)).await?;
+
+        assert_eq!(customers[1].id, 2);
+        assert_eq!(customers[1].name, "Charlie");
+        assert_eq!(customers[1].credit_limit, 1000);
+
+        // A value of the wrong type is still an error
+        let result = conn
+            .query_as_by_index::<CustomerWithDefaults>("SELECT id, name, 'unlimited' FROM customer;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "credit_limit is not an integer or a real (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn null_as_default_attribute_replaces_only_null() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT, credit_limit INTEGER);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, credit_limit) VALUES (NULL, NULL);", ()).await?;
+        conn.execute("INSERT INTO customer (name, credit_limit) VALUES ('Charlie', 50);", ()).await?;
+
+        let customers = conn
+            .query_as_by_index::<CustomerWithLegacyNulls>("SELECT id, name, credit_limit FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "");
+        assert_eq!(customers[0].credit_limit, 1000);
+        assert_eq!(customers[1].name, "Charlie");
+        assert_eq!(customers[1].credit_limit, 50);
+
+        // Unlike #[turso(default)], a missing column is still an error
+        let mut statement = conn.prepare("SELECT id, credit_limit FROM customer ORDER BY id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let result = rows.map_rows(|row| CustomerWithLegacyNulls::try_from_row(row, &column_indices)).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "name (row 0)"));
+
+        // A value of the wrong type is still an error
+        let result = conn.query_as_by_index::<CustomerWithLegacyNulls>("SELECT 1, X'00', 50;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "name is not a string (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_attribute_calls_custom_conversion_function() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE account (id INTEGER PRIMARY KEY, permissions INTEGER, nickname TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO account (permissions, nickname) VALUES (3, 'eilrahC');", ()).await?;
+        conn.execute("INSERT INTO account (permissions, nickname) VALUES (1, '');", ()).await?;
+        conn.execute("INSERT INTO account (permissions, nickname) VALUES (8, NULL);", ()).await?;
+
+        let accounts = conn
+            .query_as_by_index::<AccountWithPermissions>("SELECT id, permissions, nickname FROM account WHERE id < 3 ORDER BY id;", ())
+            .await?;
+
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].id, 1);
+        assert_eq!(accounts[0].permissions, Permissions { read: true, write: true });
+        assert_eq!(accounts[0].nickname, Some("Charlie".to_string()));
+        assert_eq!(accounts[1].permissions, Permissions { read: true, write: false });
+        assert_eq!(accounts[1].nickname, None);
+
+        // Errors from the function are returned unchanged
+        let result = conn
+            .query_as_by_index::<AccountWithPermissions>("SELECT id, permissions, nickname FROM account WHERE id = 3;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "invalid permission bits Integer(8) (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn try_from_attribute_converts_with_try_from() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let subscribers = conn.query_as_by_index::<Subscriber>("SELECT 'charlie@example.com', NULL, 15;", ()).await?;
+
+        assert_eq!(subscribers.len(), 1);
+        assert_eq!(subscribers[0].email, EmailAddress("charlie@example.com".to_string()));
+        assert_eq!(subscribers[0].backup_email, None);
+        assert_eq!(subscribers[0].discount, Percentage(15));
+
+        let result = conn.query_as_by_index::<Subscriber>("SELECT 'charlie', NULL, 15;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "email: \"charlie\" is not an email address (row 0)"));
+
+        let result = conn.query_as_by_index::<Subscriber>("SELECT 'charlie@example.com', NULL, 150;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "discount: 150 is not a percentage (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flatten_attribute_maps_nested_structs_from_following_columns() -> TursoMapperResult<()> {
+        use super::FlattenRowByIndex;
+
+        assert_eq!(Address::COLUMN_COUNT, 2);
+        assert_eq!(ContactDetails::COLUMN_COUNT, 3);
+        assert_eq!(CustomerWithIdLast::COLUMN_COUNT, 3);
+        assert_eq!(CustomerWithContactDetails::COLUMN_COUNT, 7);
+
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT);", ())
+            .await?;
+        conn.execute("CREATE TABLE address (customer_id INTEGER, email TEXT, street TEXT, city TEXT);", ())
+            .await?;
+        conn.execute("INSERT INTO customer (name, description) VALUES ('Charlie', NULL);", ()).await?;
+        conn.execute("INSERT INTO address VALUES (1, 'charlie@example.com', '1 High Street', 'Leeds');", ())
+            .await?;
+
+        let customers = conn
+            .query_as_by_index::<CustomerWithContactDetails>(
+                "SELECT c.name, c.description, c.id, a.email, a.street, a.city, 3 FROM customer c JOIN address a ON a.customer_id = c.id;",
+                (),
+            )
+            .await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].customer.id, 1);
+        assert_eq!(customers[0].customer.name, "Charlie");
+        assert_eq!(customers[0].customer.description, None);
+        assert_eq!(customers[0].display_name, "");
+        assert_eq!(customers[0].contact.email, "charlie@example.com");
+        assert_eq!(customers[0].contact.address.street, "1 High Street");
+        assert_eq!(customers[0].contact.address.city, "Leeds");
+        assert_eq!(customers[0].order_count, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flatten_attribute_maps_nested_structs_by_prefixed_name() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let mut statement = conn
+            .prepare(
+                "SELECT 'Leeds' AS work_city, '2 Mill Lane' AS work_street, 'charlie@example.com' AS work_email, \
+                 'York' AS home_city, '1 High Street' AS home_street, 'Charlie' AS name, 1 AS id;",
+            )
+            .await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let customers = rows.map_rows(|row| CustomerWithAddresses::try_from_row(row, &column_indices)).await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].home.street, "1 High Street");
+        assert_eq!(customers[0].home.city, "York");
+        assert_eq!(customers[0].work.email, "charlie@example.com");
+        assert_eq!(customers[0].work.address.street, "2 Mill Lane");
+        assert_eq!(customers[0].work.address.city, "Leeds");
+
+        // A missing column is reported with its prefixed name
+        let mut statement = conn
+            .prepare("SELECT 1 AS id, 'Charlie' AS name, '1 High Street' AS home_street, 'York' AS city;")
+            .await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let result = rows.map_rows(|row| CustomerWithAddresses::try_from_row(row, &column_indices)).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "home_city (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rename_all_attribute_converts_column_names() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let mut statement = conn
+            .prepare("SELECT 'https://example.com/charlie' AS HTMLProfileURL, 'Charlie' AS displayName, 1 AS customerId;")
+            .await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let customers = rows.map_rows(|row| JsServiceCustomer::try_from_row(row, &column_indices)).await?;
+
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].customer_id, 1);
+        assert_eq!(customers[0].display_name, "Charlie");
+        assert_eq!(customers[0].profile_url, Some("https://example.com/charlie".to_string()));
+
+        let mut statement = conn.prepare("SELECT 1 AS customer_id, 'Charlie' AS displayName;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let result = rows.map_rows(|row| JsServiceCustomer::try_from_row(row, &column_indices)).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(column)) if column == "customerId (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crate_attribute_overrides_the_crate_path() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let customers = conn.query_as_by_index::<CustomerWithTier>("SELECT 1, 'Paid';", ()).await?;
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].tier, Tier::Paid);
+
+        let mut statement = conn.prepare("SELECT 'Free' AS tier, 2 AS id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let customers = rows.map_rows(|row| CustomerWithTier::try_from_row(row, &column_indices)).await?;
+        assert_eq!(customers[0].id, 2);
+        assert_eq!(customers[0].tier, Tier::Free);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn option_fields_reject_wrong_types_unless_lenient() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let measurements = conn.query_as_by_index::<MeasurementWithLenientOptions>("SELECT NULL, 42;", ()).await?;
+        assert_eq!(measurements[0].reading, None);
+        // Fields marked lenient map a value of the wrong type to None
+        assert_eq!(measurements[0].note, None);
+
+        let measurements = conn.query_as_by_index::<MeasurementWithLenientOptions>("SELECT 1.5, 'ok';", ()).await?;
+        assert_eq!(measurements[0].reading, Some(1.5));
+        assert_eq!(measurements[0].note, Some("ok".to_string()));
+
+        let result = conn.query_as_by_index::<MeasurementWithLenientOptions>("SELECT '1.5', NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "reading is not a real or an integer, got a string (row 0)"));
+
+        let customers = conn.query_as_by_index::<LenientCustomer>("SELECT 1, 42, 5;", ()).await?;
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].description, None);
+        assert_eq!(customers[0].rating, Some(5));
+
+        // A strict field stays strict on a lenient struct
+        let result = conn.query_as_by_index::<LenientCustomer>("SELECT 1, 'Regular', 'five';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "rating is not an integer or a real, got a string (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn option_fields_fail_when_the_row_has_too_few_columns() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let result = conn.query_as_by_index::<MeasurementWithLenientOptions>("SELECT 1.5;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(msg)) if msg == "index 1 of a row with 1 columns (row 0)"));
+
+        let result = conn.query_as_by_index::<LenientCustomer>("SELECT 1;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ColumnNotFound(msg)) if msg == "index 1 of a row with 1 columns (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn float_fields_accept_whole_numbers_returned_as_integers() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE reading (id INTEGER PRIMARY KEY, value REAL, weight REAL);", ())
+            .await?;
+        conn.execute("INSERT INTO reading (id, value, weight) VALUES (1, 3, NULL);", ()).await?;
+
+        let points = conn.query_as::<Point>("SELECT value, 3 FROM reading;", ()).await?;
+        assert_eq!((points[0].0, points[0].1), (3.0, 3.0));
+
+        let measurements = conn.query_as::<MeasurementWithLenientOptions>("SELECT 2, 'ok';", ()).await?;
+        assert_eq!(measurements[0].reading, Some(2.0));
+
+        let result = conn.query_as::<Point>("SELECT 'three', 3;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "field 0 is not a real or an integer (row 0)"));
+
+        let result = conn.query_as::<Point>("SELECT NULL, 3;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "field 0 (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn integer_fields_accept_whole_numbers_returned_as_reals() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let counts = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 42.0, -8.0, -16.0, -32.0, -64.0, 8.0, 16.0, 32.0, 64.0, 128.0, 7.0, 0.0;", ())
+            .await?;
+        assert_eq!(counts[0].total, 42);
+        assert_eq!((counts[0].tiny, counts[0].small, counts[0].medium, counts[0].size), (-8, -16, -32, -64));
+        assert_eq!(
+            (
+                counts[0].unsigned_tiny,
+                counts[0].unsigned_small,
+                counts[0].unsigned_medium,
+                counts[0].unsigned,
+                counts[0].unsigned_size
+            ),
+            (8, 16, 32, 64, 128)
+        );
+        assert_eq!(counts[0].non_zero.get(), 7);
+        assert_eq!(counts[0].optional, Some(0));
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 42.5, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, NULL;", ())
+            .await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "total value 42.5 cannot be converted to an integer without losing precision (row 0)")
+        );
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 1, 128.0, 1, 1, 1, 1, 1, 1, 1, 1, 1, NULL;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "tiny value 128 is out of range for i8 (row 0)"));
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0.0, NULL;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "non_zero must not be zero (row 0)"));
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 1, 1, 1, 1, 1, -1.0, 1, 1, 1, 1, 1, NULL;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "unsigned_tiny value -1 is out of range for u8 (row 0)"));
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1.25;", ())
+            .await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "optional value 1.25 cannot be converted to an integer without losing precision (row 0)")
+        );
+
+        // i64::MAX as f64 rounds up to 2^63, which does not fit, while the next real down and i64::MIN both do
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 9223372036854775807.0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, NULL;", ())
+            .await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "total value 9223372036854776000 cannot be converted to an integer without losing precision (row 0)")
+        );
+
+        let counts = conn
+            .query_as_by_index::<AggregateCounts>("SELECT 9223372036854774784.0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -9223372036854775808.0;", ())
+            .await?;
+        assert_eq!(counts[0].total, 9223372036854774784);
+        assert_eq!(counts[0].optional, Some(i64::MIN));
+
+        let result = conn
+            .query_as_by_index::<AggregateCounts>("SELECT -9223372036854777856.0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, NULL;", ())
+            .await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "total value -9223372036854778000 cannot be converted to an integer without losing precision (row 0)")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parse_text_attribute_parses_numbers_stored_as_text() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let invoices = conn.query_as_by_index::<LegacyInvoice>("SELECT 1, '42', '12.75', '17';", ()).await?;
+        assert_eq!(invoices[0].id, 1);
+        assert_eq!(invoices[0].quantity, 42);
+        assert_eq!(invoices[0].amount, Some(12.75));
+        assert_eq!(invoices[0].reference, "17");
+
+        // Numbers stored as numbers still work, and NULL is still None
+        let invoices = conn.query_as_by_index::<LegacyInvoice>("SELECT 1, 42, NULL, 'A-17';", ()).await?;
+        assert_eq!(invoices[0].quantity, 42);
+        assert_eq!(invoices[0].amount, None);
+
+        let result = conn.query_as_by_index::<LegacyInvoice>("SELECT 1, 'forty', '12.75', 'A-17';", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "quantity value \"forty\" is not a valid number: invalid digit found in string (row 0)")
+        );
+
+        // The parsed number is range checked like a stored integer
+        let result = conn.query_as_by_index::<LegacyInvoice>("SELECT 1, '300', '12.75', 'A-17';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "quantity value 300 is out of range for u8 (row 0)"));
+
+        // Fields without the attribute keep rejecting text
+        let result = conn.query_as_by_index::<LegacyInvoice>("SELECT '1', '42', '12.75', 'A-17';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "id is not an integer or a real (row 0)"));
+
+        let totals = conn.query_as_by_index::<LegacyTotals>("SELECT '12', '2.5', 'all';", ()).await?;
+        assert_eq!(totals[0].count, 12);
+        assert_eq!(totals[0].average, 2.5);
+        assert_eq!(totals[0].label, "all");
+
+        let result = conn.query_as_by_index::<LegacyTotals>("SELECT '12', X'00', 'all';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "average is not a real, an integer or a numeric string (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bool_text_attribute_reads_flags_stored_as_text() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let flags = conn
+            .query_as_by_index::<LegacyFlags>("SELECT 'Y', 'false' UNION ALL SELECT 'n', 'TRUE' UNION ALL SELECT 1, NULL;", ())
+            .await?;
+        assert_eq!(
+            flags.iter().map(|flag| (flag.active, flag.verified)).collect::<Vec<_>>(),
+            [(true, Some(false)), (false, Some(true)), (true, None)]
+        );
+
+        let result = conn.query_as_by_index::<LegacyFlags>("SELECT 'yes', NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "active must be \"Y\" or \"N\" to map to a bool, got \"yes\" (row 0)"));
+
+        let result = conn.query_as_by_index::<LegacyFlags>("SELECT 'N', 'f';", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "verified must be \"true\" or \"false\" to map to a bool, got \"f\" (row 0)")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn coerce_on_the_struct_applies_to_every_numeric_field() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let reports = conn.query_as_by_index::<AdHocReport>("SELECT '12', 4, 3, '7';", ()).await?;
+        assert_eq!(reports[0].total, 12);
+        assert_eq!(reports[0].average, 4.0);
+        assert_eq!(reports[0].row_count, 3);
+        assert_eq!(reports[0].label, "7");
+
+        let reports = conn.query_as_by_index::<AdHocReport>("SELECT 12.0, '4.5', 3, 'all';", ()).await?;
+        assert_eq!(reports[0].total, 12);
+        assert_eq!(reports[0].average, 4.5);
+
+        // A field opting out reads only integers
+        let result = conn.query_as_by_index::<AdHocReport>("SELECT 12, 4.5, 3.0, 'all';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "row_count is not an integer (row 0)"));
+
+        // coerce alone converts between integers and reals, but leaves text to parse_text
+        let totals = conn.query_as_by_index::<CoercedTotals>("SELECT 12.0, 4;", ()).await?;
+        assert_eq!(totals[0].total, 12);
+        assert_eq!(totals[0].average, 4.0);
+
+        let result = conn.query_as_by_index::<CoercedTotals>("SELECT '12', 4;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "total is not an integer or a real (row 0)"));
+
+        let readings = conn.query_as_by_index::<ExactReading>("SELECT 2.5, '3';", ()).await?;
+        assert_eq!(readings[0].value, 2.5);
+        assert_eq!(readings[0].count, 3);
+
+        let result = conn.query_as_by_index::<ExactReading>("SELECT 2, 3;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "value is not a real (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn coerce_attribute_converts_between_integer_and_real() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let readings = conn.query_as_by_index::<CoercedReading>("SELECT 3, 2, 4.0, 5.0, 6.0;", ()).await?;
+        assert_eq!(readings[0].value, 3.0);
+        assert_eq!(readings[0].weight, 2.0);
+        assert_eq!(readings[0].count, 4);
+        assert_eq!(readings[0].small_count, 5);
+        assert_eq!(readings[0].limit, Some(6));
+
+        // The usual storage classes still work
+        let readings = conn.query_as_by_index::<CoercedReading>("SELECT 3.5, 2.5, 4, 5, NULL;", ()).await?;
+        assert_eq!(readings[0].value, 3.5);
+        assert_eq!(readings[0].weight, 2.5);
+        assert_eq!(readings[0].count, 4);
+        assert_eq!(readings[0].limit, None);
+
+        // Lossy conversions are still errors
+        let result = conn.query_as_by_index::<CoercedReading>("SELECT 3, 2, 3.5, 5, NULL;", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "count value 3.5 cannot be converted to an integer without losing precision (row 0)")
+        );
+
+        let result = conn.query_as_by_index::<CoercedReading>("SELECT 9007199254740993, 2, 4, 5, NULL;", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "value value 9007199254740993 cannot be converted to a real without losing precision (row 0)")
+        );
+
+        let result = conn.query_as_by_index::<CoercedReading>("SELECT 3, 16777217, 4, 5, NULL;", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "weight value 16777217 cannot be converted to an f32 without losing precision (row 0)")
+        );
+
+        let result = conn.query_as_by_index::<CoercedReading>("SELECT 3, 2, 4, 300.0, NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "small_count value 300 is out of range for u8 (row 0)"));
+
+        let result = conn.query_as_by_index::<CoercedReading>("SELECT 3, 2, 4, 5, 1e300;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg.starts_with("limit value 1")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn validate_attribute_checks_field_values() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let orders = conn.query_as_by_index::<ValidatedOrder>("SELECT 'A-1', 9.99, NULL;", ()).await?;
+        assert_eq!(orders[0].reference, "A-1");
+        assert_eq!(orders[0].amount, 9.99);
+        assert_eq!(orders[0].discount, None);
+
+        let result = conn.query_as_by_index::<ValidatedOrder>("SELECT '  ', 9.99, NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "reference: must not be blank (row 0)"));
+
+        let result = conn.query_as_by_index::<ValidatedOrder>("SELECT 'A-1', -1.0, NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "amount: must be positive (row 0)"));
+
+        let result = conn.query_as_by_index::<ValidatedOrder>("SELECT 'A-1', 9.99, 150;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "discount: 150 is not a percentage (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn table_attribute_adds_table_constant() -> TursoMapperResult<()> {
+        assert_eq!(CustomerRecord::TABLE, "customer");
+        assert_eq!(CustomerNote::TABLE, "customer_note");
+
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL);", ()).await?;
+        conn.execute("INSERT INTO customer (name) VALUES ('Charlie');", ()).await?;
+
+        let sql = format!("SELECT id, name FROM {};", CustomerRecord::TABLE);
+        let customers = conn.query_as_by_index::<CustomerRecord>(&sql, ()).await?;
+        assert_eq!(customers[0].id, 1);
+        assert_eq!(customers[0].name, "Charlie");
+
+        // A struct mapped only by name gets the constant too
+        conn.execute("CREATE TABLE customer_note (id INTEGER PRIMARY KEY, note TEXT NOT NULL);", ())
+            .await?;
+        conn.execute("INSERT INTO customer_note (note) VALUES ('Prefers email');", ()).await?;
+
+        let sql = format!("SELECT id, note FROM {};", CustomerNote::TABLE);
+        let notes = conn.query_as_by_name::<CustomerNote>(&sql, ()).await?;
+        assert_eq!(notes[0].note, "Prefers email");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn to_params_binds_fields_as_parameters() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+
+        let charlie = NewCustomer {
+            name: "Charlie".to_string(),
+            balance: 3.12,
+            image: vec![0, 1, 2, 3],
+            description: Some("First customer".to_string()),
+            cached_total: 42,
+        };
+        let sarah = NewCustomer {
+            name: "Sarah".to_string(),
+            balance: 0.99,
+            image: vec![9, 8, 7, 6],
+            description: None,
+            cached_total: 0,
+        };
+
+        conn.execute(
+            "INSERT INTO customer (name, value, image, description) VALUES (?1, ?2, ?3, ?4);",
+            charlie.to_params(),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, image, description) VALUES (:name, :value, :image, :description);",
+            sarah.to_named_params(),
+        )
+        .await?;
+
+        let customers = conn
+            .query_as_by_index::<NewCustomer>("SELECT name, value, image, description FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers.len(), 2);
+        assert_eq!(customers[0].name, "Charlie");
+        assert_eq!(customers[0].balance, 3.12);
+        assert_eq!(customers[0].image, vec![0, 1, 2, 3]);
+        assert_eq!(customers[0].description, Some("First customer".to_string()));
+        assert_eq!(customers[1].name, "Sarah");
+        assert_eq!(customers[1].balance, 0.99);
+        assert_eq!(customers[1].image, vec![9, 8, 7, 6]);
+        assert_eq!(customers[1].description, None);
+        assert_eq!(customers[1].cached_total, 0);
+
+        let names = sarah.to_named_params().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, [":name", ":value", ":image", ":description"]);
+        assert_eq!(sarah.into_params().len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_value_binds_wide_integers_chars_and_derived_enums() -> TursoMapperResult<()> {
+        assert_eq!((-7isize).to_value(), turso::Value::Integer(-7));
+        assert_eq!(42usize.to_value(), turso::Value::Integer(42));
+        assert_eq!(u64::MAX.to_value(), turso::Value::Integer(-1));
+        assert_eq!('é'.to_value(), turso::Value::Text("é".to_string()));
+
+        assert_eq!(OrderStatus::OnHold.to_value(), turso::Value::Text("on_hold".to_string()));
+        assert_eq!(OrderStatus::Cancelled.to_value(), turso::Value::Text("canceled".to_string()));
+        assert_eq!(Priority::Urgent.to_value(), turso::Value::Integer(5));
+        assert_eq!(Visibility::Hidden.to_value(), turso::Value::Integer(-1));
+        assert_eq!(DeliveryChannel::Sms.to_value(), turso::Value::Text("sms".to_string()));
+        assert_eq!(DeliveryChannel::Unknown("fax".to_string()).to_value(), turso::Value::Text("fax".to_string()));
+
+        // Each variant reads back from the value it is written as
+        assert_eq!(OrderStatus::try_from_value(OrderStatus::Shipped.to_value())?, OrderStatus::Shipped);
+        assert_eq!(Priority::try_from_value(Priority::Normal.to_value())?, Priority::Normal);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn try_from_row_derive_maps_by_index_and_by_name() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let accounts = conn.query_as_by_index::<AccountSummary>("SELECT 1, 'Charlie', 3, NULL, 'Chaz';", ()).await?;
+        assert_eq!(accounts[0].id, 1);
+        assert_eq!(accounts[0].account_name, "Charlie");
+        assert_eq!(accounts[0].permissions, Permissions { read: true, write: true });
+        assert_eq!(accounts[0].balance, 0.0);
+        assert_eq!(accounts[0].nickname, Some("Chaz".to_string()));
+
+        // The balance column is missing, so the default is used
+        let mut statement = conn.prepare("SELECT NULL AS nickname, 1 AS permissions, 'Sarah' AS name, 2 AS id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let accounts = rows.map_rows(|row| AccountSummary::try_from_row(row, &column_indices)).await?;
+        assert_eq!(accounts[0].id, 2);
+        assert_eq!(accounts[0].account_name, "Sarah");
+        assert_eq!(accounts[0].permissions, Permissions { read: true, write: false });
+        assert_eq!(accounts[0].balance, 0.0);
+        assert_eq!(accounts[0].nickname, None);
+
+        let result = conn.query_as_by_index::<AccountSummary>("SELECT 1, 'Charlie', 8, NULL, NULL;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "invalid permission bits Integer(8) (row 0)"));
+
+        // The derived type implements the TryFromRow trait, which requires both mappings
+        async fn both_ways<T: TryFromRow>(db: &impl QueryAs, sql: &str) -> TursoMapperResult<(Vec<T>, Vec<T>)> {
+            Ok((db.query_as(sql, ()).await?, db.query_as_by_name(sql, ()).await?))
+        }
+
+        let (by_index, by_name) =
+            both_ways::<AccountSummary>(&conn, "SELECT 3 AS id, 'Ann' AS name, 2 AS permissions, 1.5 AS balance, NULL AS nickname;").await?;
+        assert_eq!(by_index[0].account_name, by_name[0].account_name);
+        assert_eq!(by_index[0].balance, by_name[0].balance);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_derive_inserts_rows() -> TursoMapperResult<()> {
+        assert_eq!(
+            StoredCustomer::insert_sql(),
+            r#"INSERT INTO "customer" ("name", "value", "description") VALUES (?, ?, ?)"#
+        );
+
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+
+        let mut charlie = StoredCustomer {
+            id: 0,
+            customer_name: "Charlie".to_string(),
+            value: 3.12,
+            description: Some("First customer".to_string()),
+            cached_total: 42,
+        };
+        let sarah = StoredCustomer {
+            id: 0,
+            customer_name: "Sarah".to_string(),
+            value: 0.99,
+            description: None,
+            cached_total: 0,
+        };
+
+        charlie.id = StoredCustomer::insert(&conn, &charlie).await?;
+        assert_eq!(charlie.id, 1);
+        assert_eq!(StoredCustomer::insert(&conn, &sarah).await?, 2);
+
+        let customers = conn
+            .query_as_by_index::<StoredCustomer>("SELECT id, name, value, description FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers[0], StoredCustomer { cached_total: 0, ..charlie });
+        assert_eq!(customers[1], StoredCustomer { id: 2, ..sarah });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_works_inside_a_transaction() -> TursoMapperResult<()> {
+        // Written against the trait so it runs the same on a connection and a transaction
+        async fn customer_names(db: &impl QueryAs) -> TursoMapperResult<Vec<String>> {
+            db.query_scalars("SELECT name FROM customer ORDER BY id;", ()).await
+        }
+
+        let db = Builder::new_local(":memory:").build().await?;
+        let mut conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL);", ())
+            .await?;
+
+        let tx = conn.transaction().await?;
+        let sarah = StoredCustomer {
+            id: 0,
+            customer_name: "Sarah".to_string(),
+            value: 0.99,
+            description: Some("Regular".to_string()),
+            cached_total: 0,
+        };
+        let id = StoredCustomer::insert(&tx, &sarah).await?;
+
+        let customers = tx
+            .query_as::<StoredCustomer>("SELECT id, name, value, description FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers.len(), 2);
+        assert_eq!(customers[1], StoredCustomer { id, ..sarah });
+        assert_eq!(
+            tx.query_one::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [id])
+                .await?
+                .customer_name,
+            "Sarah"
+        );
+        assert_eq!(customer_names(&tx).await?, ["Charlie", "Sarah"]);
+
+        tx.rollback().await?;
+
+        assert_eq!(customer_names(&conn).await?, ["Charlie"]);
+        let result = conn
+            .query_optional::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [id])
+            .await?;
+        assert_eq!(result, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn prepared_statements_map_rows_each_time_they_run() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Ann', 7.5, 'New');",
+            (),
+        )
+        .await?;
+
+        let mut statement = conn
+            .prepare("SELECT id, name, value, description FROM customer WHERE value > ? ORDER BY id;")
+            .await?;
+        let customers = statement.query_as::<StoredCustomer>([1.0]).await?;
+        assert_eq!(customers.iter().map(|customer| customer.id).collect::<Vec<_>>(), [1, 3]);
+        let customers = statement.query_as::<StoredCustomer>([5.0]).await?;
+        assert_eq!(customers.iter().map(|customer| customer.id).collect::<Vec<_>>(), [3]);
+
+        let mut statement = conn.prepare("SELECT id, name, value, description FROM customer WHERE id = ?;").await?;
+        assert_eq!(statement.query_one::<StoredCustomer>([2]).await?.customer_name, "Sarah");
+        assert_eq!(statement.query_one::<StoredCustomer>([3]).await?.customer_name, "Ann");
+        let result = statement.query_one::<StoredCustomer>([4]).await;
+        assert!(matches!(result, Err(TursoMapperError::NoRows(sql)) if sql == "prepared statement"));
+        assert_eq!(statement.query_optional::<StoredCustomer>([4]).await?, None);
+        assert_eq!(statement.query_optional::<StoredCustomer>([1]).await?.map(|customer| customer.id), Some(1));
+
+        let mut statement = conn.prepare("SELECT id, name, value, description FROM customer WHERE value > ?;").await?;
+        let result = statement.query_optional::<StoredCustomer>([1.0]).await;
+        assert!(matches!(result, Err(TursoMapperError::TooManyRows(sql)) if sql == "prepared statement"));
+
+        let mut statement = conn.prepare("SELECT COUNT(*) FROM customer WHERE value > ?;").await?;
+        assert_eq!(statement.query_scalar::<usize>([1.0]).await?, 2);
+        assert_eq!(statement.query_scalar::<usize>([100.0]).await?, 0);
+
+        // The columns are selected in a different order to the struct fields
+        let mut statement = conn.prepare("SELECT description, name, id FROM customer WHERE id = ?;").await?;
+        let customers = statement.query_as_by_name::<CustomerSummary>([2]).await?;
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].customer_name, "Sarah");
+        assert_eq!(customers[0].description.as_deref(), Some("Regular"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_derive_updates_rows_by_primary_key() -> TursoMapperResult<()> {
+        assert_eq!(
+            StoredCustomer::update_sql(),
+            r#"UPDATE "customer" SET "name" = ?, "value" = ?, "description" = ? WHERE "id" = ?"#
+        );
+
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, 'First customer');",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO customer (name, value, description) VALUES ('Sarah', 0.99, NULL);", ())
+            .await?;
+
+        let charlie = StoredCustomer {
+            id: 1,
+            customer_name: "Chaz".to_string(),
+            value: 4.5,
+            description: None,
+            cached_total: 42,
+        };
+        assert_eq!(charlie.update(&conn).await?, 1);
+
+        let customers = conn
+            .query_as_by_index::<StoredCustomer>("SELECT id, name, value, description FROM customer ORDER BY id;", ())
+            .await?;
+        assert_eq!(customers[1].customer_name, "Sarah");
+
+        let missing = StoredCustomer { id: 3, ..charlie };
+        assert_eq!(missing.update(&conn).await?, 0);
+        assert_eq!(
+            customers[0],
+            StoredCustomer {
+                id: 1,
+                cached_total: 0,
+                ..missing
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_one_requires_exactly_one_row() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular');",
+            (),
+        )
+        .await?;
+
+        let sarah = conn
+            .query_one::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [2])
+            .await?;
+        assert_eq!(sarah.customer_name, "Sarah");
+        assert_eq!(sarah.description, Some("Regular".to_string()));
+
+        let result = conn
+            .query_one::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = 3;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NoRows(sql)) if sql == "SELECT id, name, value, description FROM customer WHERE id = 3;"));
+
+        let result = conn.query_one::<StoredCustomer>("SELECT id, name, value, description FROM customer;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::TooManyRows(sql)) if sql == "SELECT id, name, value, description FROM customer;"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_optional_allows_at_most_one_row() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular');",
+            (),
+        )
+        .await?;
+
+        let charlie = conn
+            .query_optional::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [1])
+            .await?;
+        assert_eq!(charlie.map(|customer| customer.customer_name), Some("Charlie".to_string()));
+
+        let missing = conn
+            .query_optional::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [3])
+            .await?;
+        assert!(missing.is_none());
+
+        let result = conn
+            .query_optional::<StoredCustomer>("SELECT id, name, value, description FROM customer;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::TooManyRows(sql)) if sql == "SELECT id, name, value, description FROM customer;"));
+
+        // A row that cannot be mapped is an error rather than None
+        let result = conn
+            .query_optional::<StoredCustomer>("SELECT id, name, 'free', description FROM customer WHERE id = 1;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "value is not a real or an integer"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_map_indexes_rows_by_key() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Sarah', 7.5, 'New');",
+            (),
+        )
+        .await?;
+        let sql = "SELECT id, name, value, description FROM customer ORDER BY id;";
+
+        let by_id = conn.query_as_map(sql, (), |customer: &StoredCustomer| customer.id).await?;
+        assert_eq!(by_id.len(), 3);
+        assert_eq!(by_id[&2].customer_name, "Sarah");
+        assert_eq!(by_id[&2].value, 0.99);
+
+        let result = conn.query_as_map(sql, (), |customer: &StoredCustomer| customer.customer_name.clone()).await;
+        assert!(matches!(result, Err(TursoMapperError::DuplicateKey(msg)) if msg == "\"Sarah\" (row 2)"));
+
+        let by_name = conn
+            .query_as_map_last_wins(sql, (), |customer: &StoredCustomer| customer.customer_name.clone())
+            .await?;
+        assert_eq!(by_name.len(), 2);
+        assert_eq!(by_name["Sarah"].id, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_grouped_groups_rows_by_key() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Sarah', 7.5, 'New');",
+            (),
+        )
+        .await?;
 
-        let customer = CustomerWithManualTryFromRow::try_from_row_by_index(row)?;
+        let by_name = conn
+            .query_as_grouped(
+                "SELECT id, name, value, description FROM customer ORDER BY id DESC;",
+                (),
+                |customer: &StoredCustomer| customer.customer_name.clone(),
+            )
+            .await?;
+        assert_eq!(by_name.len(), 2);
+        assert_eq!(by_name["Sarah"].iter().map(|customer| customer.id).collect::<Vec<_>>(), [3, 2]);
+        assert_eq!(by_name["Charlie"].len(), 1);
 
-        assert_eq!(customer.id, 1);
-        assert_eq!(customer.name, "Charlie");
-        assert_eq!(customer.value, 3.12);
-        assert_eq!(customer.image, vec![1, 2, 3]);
+        let result = conn
+            .query_as_grouped("SELECT id, description, value, name FROM customer;", (), |customer: &StoredCustomer| {
+                customer.id
+            })
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "customer_name (row 0)"));
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn derive_macro_try_from_row_impl_works() -> TursoMapperResult<()> {
-        let row: Row = Row::from_iter(
-            [
-                Value::Integer(1),
-                Value::Text(Text::new("Charlie")),
-                Value::Float(3.12),
-                Value::Blob(vec![1, 2, 3]),
-            ]
-            .iter(),
-        );
+    async fn query_as_limited_guards_against_unexpectedly_large_results() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
 
-        let customer = Customer::try_from_row_by_index(row)?;
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Ann', 7.5, 'New');",
+            (),
+        )
+        .await?;
 
-        assert_eq!(customer.id, 1);
-        assert_eq!(customer.name, "Charlie");
-        assert_eq!(customer.value, 3.12);
-        assert_eq!(customer.image, vec![1, 2, 3]);
+        let customers = conn
+            .query_as_limited::<StoredCustomer>("SELECT id, name, value, description FROM customer WHERE id = ?;", [2], 1)
+            .await?;
+        assert_eq!(customers.len(), 1);
+        assert_eq!(customers[0].customer_name, "Sarah");
+
+        // The WHERE clause is missing, so the query returns every customer
+        let result = conn
+            .query_as_limited::<StoredCustomer>("SELECT id, name, value, description FROM customer;", (), 2)
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::RowLimitExceeded { limit: 2 })));
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn end_to_end_test_with_map_rows_and_try_from_row() -> TursoMapperResult<()> {
+    async fn for_each_as_passes_each_mapped_row_to_the_function() -> TursoMapperResult<()> {
         let db = Builder::new_local(":memory:").build().await?;
         let conn = db.connect()?;
 
         conn.execute(
-            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Ann', 7.5, 'New');",
             (),
         )
         .await?;
 
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+        let mut names = vec![];
+        conn.for_each_as(
+            "SELECT id, name, value, description FROM customer ORDER BY name;",
+            (),
+            |customer: StoredCustomer| {
+                names.push(customer.customer_name);
+                Ok(())
+            },
+        )
+        .await?;
+        assert_eq!(names, ["Ann", "Charlie", "Sarah"]);
+
+        // An error from the function stops reading further rows
+        let mut names = vec![];
+        let result = conn
+            .for_each_as(
+                "SELECT id, name, value, description FROM customer ORDER BY id;",
+                (),
+                |customer: StoredCustomer| {
+                    if customer.description.is_some() {
+                        return Err(TursoMapperError::ConversionError(format!("{} is not new", customer.customer_name)));
+                    }
+                    names.push(customer.customer_name);
+                    Ok(())
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "Sarah is not new (row 1)"));
+        assert_eq!(names, ["Charlie"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_as_with_capacity_maps_like_query_as() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, NULL), ('Sarah', 0.99, 'Regular'), ('Ann', 7.5, 'New');",
+            (),
+        )
+        .await?;
+        let sql = "SELECT id, name, value, description FROM customer ORDER BY name LIMIT ?;";
+
+        let hinted = conn.query_as_with_capacity::<StoredCustomer>(sql, [10], 10).await?;
+        assert!(hinted.capacity() >= 10);
+        assert_eq!(hinted, conn.query_as::<StoredCustomer>(sql, [10]).await?);
+
+        // A hint smaller than the row count only costs the usual reallocations
+        let hinted = conn.query_as_with_capacity::<StoredCustomer>(sql, [10], 1).await?;
+        assert_eq!(hinted, conn.query_as::<StoredCustomer>(sql, [10]).await?);
+
+        let result = conn
+            .query_as_with_capacity::<StoredCustomer>("SELECT id, description, value, name FROM customer;", (), 3)
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "customer_name (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_scalars_read_the_first_column() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, credit_limit INTEGER);", ())
             .await?;
+        conn.execute(
+            "INSERT INTO customer (name, credit_limit) VALUES ('Charlie', 50), ('Sarah', NULL), ('Ann', 300);",
+            (),
+        )
+        .await?;
 
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+        let count = conn.query_scalar::<usize>("SELECT COUNT(*) FROM customer;", ()).await?;
+        assert_eq!(count, 3);
+
+        let average = conn.query_scalar::<f64>("SELECT AVG(credit_limit) FROM customer;", ()).await?;
+        assert_eq!(average, 175.0);
+
+        let result = conn.query_scalar::<i64>("SELECT id FROM customer WHERE name = 'Bob';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::NoRows(_))));
+
+        let name = conn.query_scalar_optional::<String>("SELECT name FROM customer WHERE id = ?;", [2]).await?;
+        assert_eq!(name.as_deref(), Some("Sarah"));
+
+        let name = conn.query_scalar_optional::<String>("SELECT name FROM customer WHERE id = ?;", [4]).await?;
+        assert_eq!(name, None);
+
+        let result = conn.query_scalar_optional::<String>("SELECT name FROM customer;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::TooManyRows(sql)) if sql == "SELECT name FROM customer;"));
+
+        // A nullable column is read as an Option, and NULL is an error otherwise
+        let limit = conn.query_scalar::<Option<i64>>("SELECT credit_limit FROM customer WHERE id = 2;", ()).await?;
+        assert_eq!(limit, None);
+
+        let result = conn.query_scalar::<i64>("SELECT credit_limit FROM customer WHERE id = 2;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "scalar"));
+
+        let ids = conn.query_scalars::<i64>("SELECT id FROM customer ORDER BY id;", ()).await?;
+        assert_eq!(ids, [1, 2, 3]);
+
+        let ids = conn.query_scalars::<i64>("SELECT id FROM customer WHERE name = 'Bob';", ()).await?;
+        assert!(ids.is_empty());
+
+        // The failing row is named in the error
+        let result = conn
+            .query_scalars::<u8>("SELECT credit_limit FROM customer WHERE credit_limit IS NOT NULL ORDER BY id;", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scalar value 300 is out of range for u8 (row 1)"));
+
+        let result = conn.query_scalars::<String>("SELECT credit_limit FROM customer ORDER BY id;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "scalar is not a string, got an integer (row 0)"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn query_as_stream_maps_rows_as_they_are_polled() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+        let sql = "WITH RECURSIVE n(id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < 1000) SELECT id FROM n;";
+
+        MAPPED_ROWS.set(0);
+        let mut stream = std::pin::pin!(conn.query_as_stream::<CountedId>(sql, ()));
+        assert_eq!(MAPPED_ROWS.get(), 0);
+
+        let mut expected = 1;
+        while let Some(id) = stream.try_next().await? {
+            assert_eq!(id.0, expected);
+            // Only the row just yielded has been mapped
+            assert_eq!(MAPPED_ROWS.get(), expected as usize);
+            expected += 1;
+        }
+        assert_eq!(expected, 1001);
+
+        let ids = conn
+            .query_as_stream::<CountedId>("SELECT 1 UNION ALL SELECT 2;", ())
+            .try_collect::<Vec<_>>()
             .await?;
+        assert_eq!(ids.iter().map(|id| id.0).collect::<Vec<_>>(), [1, 2]);
 
-        let customers = conn
-            .query("SELECT id, name, value, image FROM customer;", ())
-            .await?
-            .map_rows(Customer::try_from_row_by_index)
+        // The first error ends the stream
+        let results = conn
+            .query_as_stream::<CountedId>("SELECT 1 UNION ALL SELECT 'two' UNION ALL SELECT 3;", ())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 2);
+        assert!(matches!(&results[0], Ok(CountedId(1))));
+        assert!(matches!(&results[1], Err(TursoMapperError::ConversionError(msg)) if msg == "scalar is not an integer or a real, got a string (row 1)"));
+
+        let results = conn.query_as_stream::<CountedId>("SELECT * FROM missing;", ()).collect::<Vec<_>>().await;
+        assert!(matches!(results.as_slice(), [Err(TursoMapperError::TursoError(_))]));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn map_rows_stream_maps_rows_from_a_prepared_statement() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let mut statement = conn
+            .prepare("WITH RECURSIVE n(id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < ?) SELECT id FROM n;")
             .await?;
 
-        assert_eq!(customers.len(), 2);
+        MAPPED_ROWS.set(0);
+        let rows = statement.query([5]).await?;
+        let mut chunks = std::pin::pin!(rows.map_rows_stream(CountedId::try_from_row_by_index).chunks(2));
 
-        assert_eq!(customers[0].id, 1);
-        assert_eq!(customers[0].name, "Charlie");
-        assert_eq!(customers[0].value, 3.12);
-        assert_eq!(customers[0].image, vec![0, 1, 2, 3]);
+        // Each chunk maps only the rows it holds
+        let chunk = chunks.next().await.unwrap();
+        assert_eq!(chunk.into_iter().map(|id| id.map(|id| id.0)).collect::<TursoMapperResult<Vec<_>>>()?, [1, 2]);
+        assert_eq!(MAPPED_ROWS.get(), 2);
 
-        assert_eq!(customers[1].id, 2);
-        assert_eq!(customers[1].name, "Sarah");
-        assert_eq!(customers[1].value, 0.99);
-        assert_eq!(customers[1].image, vec![9, 8, 7, 6]);
+        let rest = chunks.collect::<Vec<_>>().await;
+        assert_eq!(rest.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(MAPPED_ROWS.get(), 5);
+
+        let rows = statement.query([3]).await?;
+        let names = rows
+            .map_rows_stream(|row| Ok(format!("row {}", i64::try_from_value(row.get_value(0)?)?)))
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(names, ["row 1", "row 2", "row 3"]);
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn end_to_end_test_with_query_as_by_index() -> TursoMapperResult<()> {
+    async fn find_by_id_maps_the_row_with_the_primary_key() -> TursoMapperResult<()> {
         let db = Builder::new_local(":memory:").build().await?;
         let conn = db.connect()?;
 
         conn.execute(
-            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, image BLOB NOT NULL);",
+            "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, value REAL NOT NULL, description TEXT);",
             (),
         )
         .await?;
-
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Charlie', 3.12, x'00010203');", ())
+        conn.execute(
+            "INSERT INTO customer (name, value, description) VALUES ('Charlie', 3.12, 'First customer');",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO customer (name, value, description) VALUES ('Sarah', 0.99, NULL);", ())
             .await?;
 
-        conn.execute("INSERT INTO customer (name, value, image) VALUES ('Sarah', 0.99, x'09080706');", ())
+        let sarah = StoredCustomer::find_by_id(&conn, &2).await?.unwrap();
+        assert_eq!(sarah.id, 2);
+        assert_eq!(sarah.customer_name, "Sarah");
+        assert_eq!(sarah.value, 0.99);
+        assert_eq!(sarah.description, None);
+
+        assert!(StoredCustomer::find_by_id(&conn, &3).await?.is_none());
+
+        // Without a unique key more than one row can match
+        conn.execute("CREATE TABLE visit (customer_id INTEGER NOT NULL, page TEXT NOT NULL);", ())
+            .await?;
+        conn.execute("INSERT INTO visit (customer_id, page) VALUES (1, 'home'), (1, 'basket'), (2, 'home');", ())
             .await?;
 
-        let customers = conn.query_as_by_index::<Customer>("SELECT id, name, value, image FROM customer;", ()).await?;
+        let visit = Visit::find_by_id(&conn, &2).await?.unwrap();
+        assert_eq!(visit.customer_id, 2);
+        assert_eq!(visit.page, "home");
 
-        assert_eq!(customers.len(), 2);
+        let result = Visit::find_by_id(&conn, &1).await;
+        assert!(matches!(result, Err(TursoMapperError::TooManyRows(sql)) if sql == r#"SELECT "customer_id", "page" FROM "visit" WHERE "customer_id" = ?"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deny_extra_columns_attribute_checks_the_column_count() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
 
+        let customers = conn
+            .query_as_by_index::<CustomerWithAddressColumns>("SELECT 1, '1 High Street', 'Leeds';", ())
+            .await?;
         assert_eq!(customers[0].id, 1);
-        assert_eq!(customers[0].name, "Charlie");
-        assert_eq!(customers[0].value, 3.12);
-        assert_eq!(customers[0].image, vec![0, 1, 2, 3]);
+        assert_eq!(customers[0].address.city, "Leeds");
 
-        assert_eq!(customers[1].id, 2);
-        assert_eq!(customers[1].name, "Sarah");
-        assert_eq!(customers[1].value, 0.99);
-        assert_eq!(customers[1].image, vec![9, 8, 7, 6]);
+        let result = conn
+            .query_as_by_index::<CustomerWithAddressColumns>("SELECT 1, 'Charlie', '1 High Street', 'Leeds';", ())
+            .await;
+        assert!(matches!(result, Err(TursoMapperError::ArityMismatch { expected: 3, actual: 4 })));
+
+        let result = conn.query_as_by_index::<CustomerWithAddressColumns>("SELECT 1, '1 High Street';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ArityMismatch { expected: 3, actual: 2 })));
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn option_types_support_works() -> TursoMapperResult<()> {
-        // Test with a manually created Row with some NULL values
-        let row: Row = Row::from_iter(
+    async fn error_context_attribute_is_appended_to_field_errors() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let lines = conn.query_as_by_index::<BillingLine>("SELECT 1, 2.5, 10.0;", ()).await?;
+        assert_eq!(lines[0].customer_id, 1);
+        assert_eq!(lines[0].value, Some(2.5));
+        assert_eq!(lines[0].amount, 10.0);
+
+        let result = conn.query_as_by_index::<BillingLine>("SELECT NULL, 2.5, 10.0;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::NullValue(msg)) if msg == "customer_id (customer id from billing report) (row 0)"));
+
+        let result = conn.query_as_by_index::<BillingLine>("SELECT 'one', 2.5, 10.0;", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "customer_id is not an integer or a real (customer id from billing report) (row 0)")
+        );
+
+        let result = conn.query_as_by_index::<BillingLine>("SELECT 1, 'high', 10.0;", ()).await;
+        assert!(
+            matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "value is not a real or an integer, got a string (customer value from billing report) (row 0)")
+        );
+
+        let result = conn.query_as_by_index::<BillingLine>("SELECT 1, 2.5, -1.0;", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "amount: must be positive (amount from billing report) (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tagged_enums_map_rows_to_the_variant_named_by_the_tag() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute(
+            "CREATE TABLE event (id INTEGER PRIMARY KEY, kind TEXT NOT NULL, x INTEGER, y INTEGER, amount REAL, note TEXT);",
+            (),
+        )
+        .await?;
+        conn.execute("INSERT INTO event (kind, x, y) VALUES ('click', 10, 20);", ()).await?;
+        conn.execute("INSERT INTO event (kind, amount, note) VALUES ('purchase', 9.5, 'gift');", ())
+            .await?;
+        conn.execute("INSERT INTO event (kind) VALUES ('page_view');", ()).await?;
+
+        let mut statement = conn.prepare("SELECT * FROM event ORDER BY id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let events = rows.map_rows(|row| Event::try_from_row(row, &column_indices)).await?;
+        assert_eq!(
+            events,
             [
-                Value::Integer(1),
-                Value::Text(Text::new("Charlie")),
-                Value::Float(3.12),
-                Value::Null,
-                Value::Blob(vec![1, 2, 3]),
-                Value::Null,
+                Event::Click { x: 10, y: 20 },
+                Event::BasketPurchase {
+                    total: 9.5,
+                    note: Some("gift".to_string())
+                },
+                Event::PageView,
             ]
-            .iter(),
         );
 
-        let customer = CustomerWithOptions::try_from_row_by_index(row)?;
+        // By index the tag is the first column, followed by the fields of the variant
+        assert_eq!(<Event as crate::FlattenRowByIndex>::COLUMN_COUNT, 3);
+        let events = conn.query_as_by_index::<Event>("SELECT 'purchase', 12.0, NULL;", ()).await?;
+        assert_eq!(events, [Event::BasketPurchase { total: 12.0, note: None }]);
 
-        assert_eq!(customer.id, 1);
-        assert_eq!(customer.name, "Charlie");
-        assert_eq!(customer.optional_value, Some(3.12));
-        assert_eq!(customer.optional_note, None);
-        assert_eq!(customer.optional_data, Some(vec![1, 2, 3]));
-        assert_eq!(customer.optional_count, None);
+        let result = conn.query_as_by_index::<Event>("SELECT 'scroll', 1, 2;", ()).await;
+        assert!(matches!(
+            result,
+            Err(TursoMapperError::ConversionError(msg)) if msg == "\"scroll\" is not a valid Event tag, expected one of \"click\", \"purchase\", \"page_view\" (row 0)"
+        ));
 
-        // Test with a Row with all non-NULL values
-        let row: Row = Row::from_iter(
+        let mut statement = conn
+            .prepare("SELECT 'sms' AS channel, NULL AS address UNION ALL SELECT 'Email', 'a@example.com';")
+            .await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let alerts = rows.map_rows(|row| Alert::try_from_row(row, &column_indices)).await?;
+        assert_eq!(
+            alerts,
             [
-                Value::Integer(2),
-                Value::Text(Text::new("Sarah")),
-                Value::Float(0.99),
-                Value::Text(Text::new("Some note")),
-                Value::Blob(vec![9, 8, 7, 6]),
-                Value::Integer(42),
+                Alert::Unsupported,
+                Alert::Email {
+                    address: "a@example.com".to_string()
+                }
             ]
-            .iter(),
         );
 
-        let customer = CustomerWithOptions::try_from_row_by_index(row)?;
+        Ok(())
+    }
 
-        assert_eq!(customer.id, 2);
-        assert_eq!(customer.name, "Sarah");
-        assert_eq!(customer.optional_value, Some(0.99));
-        assert_eq!(customer.optional_note, Some("Some note".to_string()));
-        assert_eq!(customer.optional_data, Some(vec![9, 8, 7, 6]));
-        assert_eq!(customer.optional_count, Some(42));
+    #[tokio::test]
+    async fn generic_structs_can_be_derived() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let records = conn.query_as_by_index::<TypedRecord<CustomerMarker>>("SELECT 1, 'Charlie';", ()).await?;
+        assert_eq!(records[0].id, 1);
+        assert_eq!(records[0].name, "Charlie");
+
+        let mut statement = conn.prepare("SELECT 'Dana' AS name, 2 AS id;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let records = rows.map_rows(|row| TypedRecord::<CustomerMarker>::try_from_row(row, &column_indices)).await?;
+        assert_eq!(records[0].id, 2);
+
+        let digests = conn.query_as_by_index::<FixedDigest<4>>("SELECT 1, X'01020304';", ()).await?;
+        assert_eq!(digests, [FixedDigest { id: 1, digest: [1, 2, 3, 4] }]);
+
+        let result = conn.query_as_by_index::<FixedDigest<2>>("SELECT 1, X'01020304';", ()).await;
+        assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "digest must be 2 bytes, got 4 (row 0)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn empty_and_unit_structs_ignore_the_row() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        conn.execute("CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL);", ()).await?;
+        conn.execute("INSERT INTO customer (id, name) VALUES (1, 'Charlie');", ()).await?;
+
+        let exists = conn.query_as_by_index::<RowExists>("SELECT 1 FROM customer WHERE id = 1;", ()).await?;
+        assert_eq!(exists, [RowExists {}]);
+        let exists = conn.query_as_by_index::<RowExists>("SELECT 1 FROM customer WHERE id = 2;", ()).await?;
+        assert!(exists.is_empty());
+
+        let mut statement = conn.prepare("SELECT id, name FROM customer;").await?;
+        let rows = statement.query(()).await?;
+        let column_indices = ColumnIndices::new(statement.columns());
+        let probes = rows.map_rows(|row| Probe::try_from_row(row, &column_indices)).await?;
+        assert_eq!(probes, [Probe]);
+        assert_eq!(<Probe as crate::FlattenRowByIndex>::COLUMN_COUNT, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn types_that_are_not_send_can_be_mapped_on_a_local_set() -> TursoMapperResult<()> {
+        let db = Builder::new_local(":memory:").build().await?;
+        let conn = db.connect()?;
+
+        let local = tokio::task::LocalSet::new();
+        let customers = local
+            .run_until(async move {
+                let handle = tokio::task::spawn_local(async move { conn.query_as::<LocalCustomer>("SELECT 'Charlie', 'charlie@example.com';", ()).await });
+                handle.await.expect("the local task completes")
+            })
+            .await?;
+        assert_eq!(&*customers[0].name, "Charlie");
+        assert_eq!(*customers[0].email, "charlie@example.com");
 
         Ok(())
     }
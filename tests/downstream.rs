@@ -0,0 +1,126 @@
+// Integration tests are compiled as a separate crate, so these derives expand as they would in a downstream crate,
+// where the generated code can only reach turso-mappers through its absolute path.
+use turso::Builder;
+use turso_mappers::{
+    ColumnIndices, Insert, MapRows, QueryAs, QueryAsByIndex, ToParams, TryFromRow, TryFromRowByIndex, TryFromRowByName, TryFromValue, TursoMapperResult, Update,
+};
+
+#[derive(Debug, PartialEq, TryFromValue)]
+#[turso(rename_all = "lowercase")]
+enum Plan {
+    Free,
+    Pro,
+}
+
+#[derive(Debug, PartialEq, TryFromValue)]
+struct CustomerId(i64);
+
+#[derive(Debug, PartialEq, TryFromRow, Insert, Update, ToParams)]
+#[turso(table = "customer")]
+struct Customer {
+    #[turso(primary_key, auto)]
+    id: i64,
+    name: String,
+    plan: Option<Plan>,
+    description: Option<String>,
+}
+
+#[derive(Debug, PartialEq, TryFromRowByIndex)]
+struct CustomerPlan {
+    id: CustomerId,
+    plan: Plan,
+}
+
+// Every name here is an SQL keyword, so the generated statements only parse with the names quoted
+#[derive(Debug, PartialEq, TryFromRowByIndex, Insert, Update)]
+#[turso(table = "order")]
+struct Order {
+    #[turso(primary_key, auto)]
+    id: i64,
+    #[turso(rename = "group")]
+    group_name: String,
+    r#match: i64,
+}
+
+#[tokio::test]
+async fn derives_compile_and_map_rows_outside_the_crate() -> TursoMapperResult<()> {
+    let db = Builder::new_local(":memory:").build().await?;
+    let conn = db.connect()?;
+
+    conn.execute(
+        "CREATE TABLE customer (id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT, plan TEXT NOT NULL DEFAULT 'free');",
+        (),
+    )
+    .await?;
+
+    let mut customer = Customer {
+        id: 0,
+        name: "Charlie".to_string(),
+        plan: Some(Plan::Pro),
+        description: None,
+    };
+    customer.id = Customer::insert(&conn, &customer).await?;
+    customer.description = Some("First customer".to_string());
+    assert_eq!(customer.update(&conn).await?, 1);
+    assert_eq!(customer.to_params().len(), 4);
+
+    let customers = conn
+        .query_as_by_index::<Customer>("SELECT id, name, plan, description FROM customer;", ())
+        .await?;
+    assert_eq!(customers, [customer]);
+
+    let mut statement = conn.prepare("SELECT description, plan, name, id FROM customer;").await?;
+    let rows = statement.query(()).await?;
+    let column_indices = ColumnIndices::new(statement.columns());
+    let customers = rows.map_rows(|row| Customer::try_from_row(row, &column_indices)).await?;
+    assert_eq!(customers[0].name, "Charlie");
+
+    let plans = conn.query_as::<CustomerPlan>("SELECT id, plan FROM customer;", ()).await?;
+    assert_eq!(
+        plans,
+        [CustomerPlan {
+            id: CustomerId(1),
+            plan: Plan::Pro
+        }]
+    );
+    assert_eq!(
+        Customer::find_by_id(&conn, &1).await?.map(|customer| customer.name),
+        Some("Charlie".to_string())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn statements_quote_keyword_table_and_column_names() -> TursoMapperResult<()> {
+    let db = Builder::new_local(":memory:").build().await?;
+    let conn = db.connect()?;
+
+    conn.execute(
+        r#"CREATE TABLE "order" (id INTEGER PRIMARY KEY, "group" TEXT NOT NULL, "match" INTEGER NOT NULL);"#,
+        (),
+    )
+    .await?;
+
+    let mut order = Order {
+        id: 0,
+        group_name: "wholesale".to_string(),
+        r#match: 3,
+    };
+    order.id = Order::insert(&conn, &order).await?;
+
+    let orders = conn.query_as::<Order>(r#"SELECT id, "group", "match" FROM "order";"#, ()).await?;
+    assert_eq!(orders[0], order);
+
+    order.group_name = "retail".to_string();
+    order.r#match = 4;
+    assert_eq!(order.update(&conn).await?, 1);
+
+    let orders = conn.query_as::<Order>(r#"SELECT id, "group", "match" FROM "order";"#, ()).await?;
+    assert_eq!(orders[0], order);
+
+    assert_eq!(Order::find_by_id(&conn, &order.id).await?, Some(order));
+    assert_eq!(Order::find_by_id(&conn, &2).await?, None);
+
+    Ok(())
+}
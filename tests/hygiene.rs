@@ -0,0 +1,116 @@
+// A local module named turso shadows the turso crate for relative paths in this crate, so the derives only compile
+// here if their generated code reaches turso through turso-mappers.
+use ::turso::Builder;
+use turso_mappers::{ColumnIndices, MapRows, QueryAs, TryFromRowByIndex, TryFromRowByName, TryFromValue, TursoMapperError, TursoMapperResult};
+
+#[allow(dead_code)]
+mod turso {}
+
+#[derive(Debug, PartialEq, TryFromRowByIndex)]
+struct Line {
+    row: i64,
+    text: Option<String>,
+}
+
+// Named like the parameters of the generated functions, which must not capture these paths
+fn row(value: ::turso::Value) -> TursoMapperResult<String> {
+    match value {
+        ::turso::Value::Text(key) => Ok(key.to_uppercase()),
+        _ => Err(TursoMapperError::ConversionError("key is not a string".to_string())),
+    }
+}
+
+fn value(value: &i64) -> Result<(), String> {
+    match *value >= 0 {
+        true => Ok(()),
+        false => Err("must not be negative".to_string()),
+    }
+}
+
+fn offset() -> i64 {
+    7
+}
+
+#[derive(Debug, PartialEq, TryFromRowByIndex, TryFromRowByName)]
+struct Setting {
+    #[turso(with = "row")]
+    row: String,
+    #[turso(validate = "value")]
+    value: i64,
+    self_: Option<String>,
+    #[turso(skip_with = "offset()")]
+    f: i64,
+}
+
+// Named like std::time::Duration, which only a bare Duration or a std::time or core::time path is taken for
+mod units {
+    #[derive(Debug, PartialEq, super::TryFromValue)]
+    pub struct Duration(pub String);
+}
+
+#[derive(Debug, PartialEq, TryFromRowByIndex)]
+struct Timer {
+    label: units::Duration,
+    elapsed: std::time::Duration,
+}
+
+#[tokio::test]
+async fn derives_do_not_depend_on_the_name_turso() -> TursoMapperResult<()> {
+    let db = Builder::new_local(":memory:").build().await?;
+    let conn = db.connect()?;
+
+    let lines = conn.query_as::<Line>("SELECT 3, 'third';", ()).await?;
+    assert_eq!(
+        lines,
+        [Line {
+            row: 3,
+            text: Some("third".to_string())
+        }]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn field_and_function_names_do_not_clash_with_generated_code() -> TursoMapperResult<()> {
+    let db = Builder::new_local(":memory:").build().await?;
+    let conn = db.connect()?;
+
+    let expected = Setting {
+        row: "THEME".to_string(),
+        value: 2,
+        self_: None,
+        f: 7,
+    };
+
+    let settings = conn.query_as::<Setting>("SELECT 'theme', 2, NULL;", ()).await?;
+    assert_eq!(settings, [expected]);
+
+    let mut statement = conn.prepare("SELECT NULL AS self_, 2 AS value, 'theme' AS row;").await?;
+    let rows = statement.query(()).await?;
+    let column_indices = ColumnIndices::new(statement.columns());
+    let settings = rows.map_rows(|row| Setting::try_from_row(row, &column_indices)).await?;
+    assert_eq!(settings[0].row, "THEME");
+
+    let result = conn.query_as::<Setting>("SELECT 'theme', -1, NULL;", ()).await;
+    assert!(matches!(result, Err(TursoMapperError::ConversionError(msg)) if msg == "value: must not be negative (row 0)"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn types_named_like_supported_types_use_their_own_impl() -> TursoMapperResult<()> {
+    let db = Builder::new_local(":memory:").build().await?;
+    let conn = db.connect()?;
+
+    let timers = conn.query_as::<Timer>("SELECT '90 minutes', 5;", ()).await?;
+    assert_eq!(
+        timers,
+        [Timer {
+            label: units::Duration("90 minutes".to_string()),
+            elapsed: std::time::Duration::from_secs(5),
+        }]
+    );
+
+    Ok(())
+}
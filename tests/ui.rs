@@ -0,0 +1,7 @@
+// Checks the compile errors reported by the derive macros against the .stderr snapshots in tests/ui.
+// Run with TRYBUILD=overwrite to update the snapshots after changing a diagnostic.
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
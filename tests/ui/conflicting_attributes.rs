@@ -0,0 +1,30 @@
+use turso_mappers::{TryFromRowByIndex, TryFromRowByName};
+
+#[derive(TryFromRowByName)]
+struct Customer {
+    id: i64,
+    #[turso(skip, rename = "display_name")]
+    name: String,
+    #[turso(skip, default)]
+    notes: Option<String>,
+}
+
+#[derive(TryFromRowByIndex)]
+struct Reading {
+    #[turso(strict, lenient)]
+    value: Option<f64>,
+}
+
+#[derive(TryFromRowByIndex)]
+struct Total {
+    #[turso(coerce = false, parse_text)]
+    value: i64,
+}
+
+#[derive(TryFromRowByIndex)]
+struct Note {
+    #[turso(text_from_blob, lossy_text)]
+    body: String,
+}
+
+fn main() {}
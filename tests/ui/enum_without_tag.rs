@@ -0,0 +1,9 @@
+use turso_mappers::TryFromRowByIndex;
+
+#[derive(TryFromRowByIndex)]
+enum Event {
+    Click { x: i64, y: i64 },
+    PageView,
+}
+
+fn main() {}
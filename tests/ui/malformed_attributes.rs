@@ -0,0 +1,39 @@
+use turso_mappers::{TryFromRowByIndex, TryFromRowByName};
+
+#[derive(TryFromRowByIndex)]
+struct UnknownAttribute {
+    #[turso(colour)]
+    id: i64,
+}
+
+#[derive(TryFromRowByName)]
+struct RenameNotAString {
+    #[turso(rename = 5)]
+    id: i64,
+}
+
+#[derive(TryFromRowByIndex)]
+#[turso(rename_all = "Title Case")]
+struct UnknownRenameRule {
+    id: i64,
+}
+
+#[derive(TryFromRowByIndex)]
+struct ParseTextOnString {
+    #[turso(parse_text)]
+    name: String,
+}
+
+#[derive(TryFromRowByIndex)]
+struct BoolTextWithoutFalse {
+    #[turso(bool_text = "Y")]
+    active: bool,
+}
+
+#[derive(TryFromRowByIndex)]
+struct PrimaryKeyWithoutTable {
+    #[turso(primary_key)]
+    id: i64,
+}
+
+fn main() {}
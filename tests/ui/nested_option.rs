@@ -0,0 +1,9 @@
+use turso_mappers::TryFromRowByIndex;
+
+#[derive(TryFromRowByIndex)]
+struct Customer {
+    id: i64,
+    description: Option<Option<String>>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use turso_mappers::TryFromRowByName;
+
+#[derive(TryFromRowByName)]
+struct Point(f64, f64);
+
+#[derive(turso_mappers::TryFromRow)]
+struct Pair(i64, String);
+
+fn main() {}
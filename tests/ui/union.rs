@@ -0,0 +1,9 @@
+use turso_mappers::TryFromRowByIndex;
+
+#[derive(TryFromRowByIndex)]
+union Number {
+    integer: i64,
+    real: f64,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use turso_mappers::{Insert, ToParams, TryFromValue};
+
+#[derive(ToParams)]
+enum Status {
+    Active,
+    Closed,
+}
+
+#[derive(Insert)]
+#[turso(table = "point")]
+struct Point(i64, i64);
+
+#[derive(TryFromValue)]
+struct Money {
+    amount: i64,
+    currency: String,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use turso_mappers::TryFromRowByIndex;
+
+#[derive(TryFromRowByIndex)]
+struct Customer {
+    id: i64,
+    location: (f64, f64),
+    tags: Option<&'static [u8]>,
+    samples: Box<[u16]>,
+    scores: Vec<i64>,
+    labels: Option<std::sync::Arc<[String]>>,
+    name: &'static str,
+}
+
+fn main() {}
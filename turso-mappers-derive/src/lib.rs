@@ -1,172 +1,1763 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{DeriveInput, Field, Ident, Type};
 
-fn impl_try_from_row_by_index(ast: DeriveInput) -> proc_macro2::TokenStream {
-    let ident: Ident = ast.ident;
+mod rename_rule;
+mod statements;
+mod tagged_enum;
+mod to_params;
+mod try_from_value;
 
-    let mut fields: Vec<Field> = vec![];
+use rename_rule::RenameRule;
 
-    match ast.data {
+// How the generated impl finds the column for each field
+#[derive(Clone, Copy, PartialEq)]
+enum RowMapping {
+    // By position, for TryFromRowByIndex
+    ByIndex,
+    // By column name through ColumnIndices, for TryFromRowByName
+    ByName,
+}
+
+// Where a field's value comes from, shared by every mapping generated for the struct
+enum FieldSource {
+    // Not read from the row, initialized with this expression
+    Skipped(proc_macro2::TokenStream),
+    // Read by the mapping of the field's own type
+    Flattened,
+    // Read from a single column and converted by the field's conversion function
+    Column {
+        // The body of the conversion function, turning `__turso_value: turso::Value` into a TursoMapperResult of the field type
+        conversion: proc_macro2::TokenStream,
+        // The value used for a column missing from a by-name mapping, instead of failing
+        default: Option<proc_macro2::TokenStream>,
+    },
+}
+
+fn impl_try_from_row(ast: DeriveInput, row_mappings: &[RowMapping]) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&ast)?;
+    let ident: Ident = ast.ident.clone();
+    let generics = ast.generics.clone();
+    let derive_name = match row_mappings {
+        [RowMapping::ByIndex] => "TryFromRowByIndex",
+        [RowMapping::ByName] => "TryFromRowByName",
+        _ => "TryFromRow",
+    };
+
+    if let Some(span) = container_attrs.deny_extra_columns
+        && !row_mappings.contains(&RowMapping::ByIndex)
+    {
+        return Err(syn::Error::new(
+            span,
+            "#[turso(deny_extra_columns)] is only supported with TryFromRowByIndex, as a mapping by name ignores other columns",
+        ));
+    }
+
+    let (mapping, table) = match ast.data {
         syn::Data::Struct(data) => {
-            for field in data.fields {
-                if field.ident.is_some() {
-                    fields.push(field)
+            let fields = data.fields;
+            // Unnamed fields have no name to look a column up by, so they are only mapped by position
+            if row_mappings.contains(&RowMapping::ByName)
+                && let syn::Fields::Unnamed(unnamed) = &fields
+            {
+                return Err(syn::Error::new_spanned(
+                    unnamed,
+                    format!(
+                        "turso_mappers::{} requires a struct with named fields, derive TryFromRowByIndex to map tuple struct fields by position",
+                        derive_name
+                    ),
+                ));
+            }
+            if let Some(tag) = &container_attrs.tag {
+                return Err(syn::Error::new(tag.span(), "#[turso(tag = \"...\")] is only supported on enums"));
+            }
+
+            let field_attrs = collect_results(fields.iter().map(FieldAttrs::from_field))?;
+            let mapping = fields_mapping(quote!(Self), &fields, &field_attrs, &container_attrs, row_mappings, row_fn_prefix(row_mappings))?;
+
+            // Every derive emits TABLE, while find_by_id reads the row by position and so needs the by-index mapping
+            let table = match &container_attrs.table {
+                Some(table) => {
+                    let find_by_id = row_mappings.contains(&RowMapping::ByIndex);
+                    statements::table_metadata(&ident, &generics, table, &fields, &field_attrs, &container_attrs, find_by_id)?
                 }
+                None => {
+                    // Only used by find_by_id and the statement derives, which all need the table
+                    if let Some(span) = field_attrs.iter().find_map(|attrs| attrs.primary_key) {
+                        return Err(syn::Error::new(span, "#[turso(primary_key)] requires #[turso(table = \"...\")] on the struct"));
+                    }
+                    quote!()
+                }
+            };
+            (mapping, table)
+        }
+        syn::Data::Enum(data) => {
+            if let Some(table) = &container_attrs.table {
+                return Err(syn::Error::new(table.span(), "#[turso(table = \"...\")] is only supported on structs"));
             }
+            (
+                tagged_enum::tagged_enum_mapping(&ident, &data, &container_attrs, row_mappings, derive_name)?,
+                quote!(),
+            )
         }
-        _ => panic!("turso_mappers::TryFromRowByIndex only supports structs"),
+        syn::Data::Union(_) => return Err(data_kind_error(&ast, format!("turso_mappers::{} only supports structs and enums", derive_name))),
     };
+    let FieldsMapping {
+        conversion_fns,
+        constructions,
+        column_count,
+    } = mapping;
 
-    let field_mappers: Vec<proc_macro2::TokenStream> = fields
-        .into_iter()
+    // Only the whole row is checked, as a flattened struct reads part of it
+    let arity_check = match container_attrs.deny_extra_columns {
+        Some(_) => quote! {
+            let __turso_expected = <Self as _turso_mappers::FlattenRowByIndex>::COLUMN_COUNT;
+            let __turso_actual = __turso_row.column_count();
+            if __turso_actual != __turso_expected {
+                return Err(_turso_mappers::TursoMapperError::ArityMismatch {
+                    expected: __turso_expected,
+                    actual: __turso_actual,
+                });
+            }
+        },
+        None => quote!(),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let impls = row_mappings.iter().zip(constructions).map(|(row_mapping, construction)| match row_mapping {
+        RowMapping::ByIndex => quote! {
+            impl #impl_generics _turso_mappers::TryFromRowByIndex for #ident #ty_generics #where_clause {
+                fn try_from_row_by_index(__turso_row: _turso_mappers::turso::Row) -> _turso_mappers::TursoMapperResult<Self> where Self: Sized {
+                    #arity_check
+                    <Self as _turso_mappers::FlattenRowByIndex>::try_from_row_at_offset(&__turso_row, 0)
+                }
+            }
+
+            impl #impl_generics _turso_mappers::FlattenRowByIndex for #ident #ty_generics #where_clause {
+                const COLUMN_COUNT: usize = #column_count;
+
+                #[allow(unused_variables)]
+                fn try_from_row_at_offset(__turso_row: &_turso_mappers::turso::Row, __turso_offset: usize) -> _turso_mappers::TursoMapperResult<Self> {
+                    Ok(#construction)
+                }
+            }
+        },
+        RowMapping::ByName => quote! {
+            impl #impl_generics _turso_mappers::TryFromRowByName for #ident #ty_generics #where_clause {
+                fn try_from_row(__turso_row: _turso_mappers::turso::Row, __turso_column_indices: &_turso_mappers::ColumnIndices) -> _turso_mappers::TursoMapperResult<Self> where Self: Sized {
+                    <Self as _turso_mappers::FlattenRowByName>::try_from_row_with_prefix(&__turso_row, __turso_column_indices, "")
+                }
+            }
+
+            impl #impl_generics _turso_mappers::FlattenRowByName for #ident #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn try_from_row_with_prefix(__turso_row: &_turso_mappers::turso::Row, __turso_column_indices: &_turso_mappers::ColumnIndices, __turso_prefix: &str) -> _turso_mappers::TursoMapperResult<Self> {
+                    Ok(#construction)
+                }
+            }
+        },
+    });
+
+    // The conversion functions are associated with the type, so they can name its generic parameters
+    Ok(container_attrs.wrap_impls(quote! {
+        #table
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#conversion_fns)*
+        }
+
+        #(#impls)*
+    }))
+}
+
+// The generated code mapping a set of fields from a row, shared by structs and the variants of tagged enums
+struct FieldsMapping {
+    // The functions converting the value of each column, called by every mapping
+    conversion_fns: Vec<proc_macro2::TokenStream>,
+    // An expression constructing the value from the row, for each row mapping
+    constructions: Vec<proc_macro2::TokenStream>,
+    // The number of columns read by the by-index mapping
+    column_count: proc_macro2::TokenStream,
+}
+
+// Maps the fields to the struct or variant named by constructor, with each conversion function name starting with
+// fn_prefix so the functions of several variants do not clash
+fn fields_mapping(
+    constructor: proc_macro2::TokenStream,
+    fields: &syn::Fields,
+    field_attrs: &[FieldAttrs],
+    container_attrs: &ContainerAttrs,
+    row_mappings: &[RowMapping],
+    fn_prefix: &str,
+) -> syn::Result<FieldsMapping> {
+    let (column_positions, column_count) = column_layout(fields, field_attrs)?;
+
+    let field_sources = collect_results(
+        fields
+            .iter()
+            .zip(field_attrs)
+            .enumerate()
+            .map(|(idx, (field, attrs))| field_source(field, &field_name(idx, field), attrs, container_attrs)),
+    )?;
+
+    // Each column is converted by one function per field, called by every mapping of the struct
+    let conversion_fns = fields
+        .iter()
+        .zip(&field_sources)
         .enumerate()
-        .map(|(idx, field)| {
-            let f_ident = field.ident.unwrap();
-            let f_type = field.ty.clone();
-
-            // Check if the field is an Option<T>
-            if let Some(inner_type) = get_option_inner_type(&f_type) {
-                // Handle Option<T> types
-                return match inner_type.as_str() {
-                    "i64" => quote! {
-                        #f_ident: match row.get_value(#idx) {
-                            Ok(value) => match value.as_integer() {
-                                Some(val) => Some(*val),
-                                None => None,
-                            },
-                            Err(_) => None,
-                        }
-                    },
-                    "String" => quote! {
-                        #f_ident: match row.get_value(#idx) {
-                            Ok(value) => match value.as_text() {
-                                Some(val) => Some(val.clone()),
-                                None => None,
-                            },
-                            Err(_) => None,
-                        }
-                    },
-                    "f64" => quote! {
-                        #f_ident: match row.get_value(#idx) {
-                            Ok(value) => match value.as_real() {
-                                Some(val) => Some(*val),
-                                None => None,
-                            },
-                            Err(_) => None,
+        .filter_map(|(idx, (field, source))| match source {
+            FieldSource::Column { conversion, .. } => {
+                let conversion_fn = conversion_fn_ident(fn_prefix, idx);
+                let f_type = &field.ty;
+                Some(quote! {
+                    fn #conversion_fn(__turso_value: _turso_mappers::turso::Value) -> _turso_mappers::TursoMapperResult<#f_type> {
+                        #conversion
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let constructions = row_mappings
+        .iter()
+        .map(|&row_mapping| {
+            let field_values = fields.iter().zip(field_attrs).zip(&field_sources).enumerate().map(|(idx, ((field, attrs), source))| {
+                let f_name = field_name(idx, field);
+                let f_type = &field.ty;
+                let column_name = container_attrs.column_name(&f_name, attrs);
+
+                let value = match source {
+                    FieldSource::Skipped(value) => value.clone(),
+                    // A flattened struct reads its own fields from the columns starting at its position, or by name
+                    // from the columns named with its prefix
+                    FieldSource::Flattened => match row_mapping {
+                        RowMapping::ByIndex => {
+                            let index = column_positions[idx].clone().expect("only skipped fields have no column position");
+                            quote_spanned! {f_type.span()=>
+                                <#f_type as _turso_mappers::FlattenRowByIndex>::try_from_row_at_offset(__turso_row, #index)?
+                            }
                         }
-                    },
-                    "Vec<u8>" => quote! {
-                        #f_ident: match row.get_value(#idx) {
-                            Ok(value) => match value.as_blob() {
-                                Some(val) => Some(val.clone()),
-                                None => None,
-                            },
-                            Err(_) => None,
+                        RowMapping::ByName => {
+                            let prefix = attrs.prefix.as_ref().map(syn::LitStr::value).unwrap_or_default();
+                            quote_spanned! {f_type.span()=>
+                                <#f_type as _turso_mappers::FlattenRowByName>::try_from_row_with_prefix(__turso_row, __turso_column_indices, &format!("{}{}", __turso_prefix, #prefix))?
+                            }
                         }
                     },
-                    _ => {
-                        // For unsupported Option<T> types, generate a compile-time error
-                        let error_msg = format!("Unsupported Option type: Option<{}>", inner_type);
-                        quote! {
-                            #f_ident: compile_error!(#error_msg)
+                    FieldSource::Column { default, .. } => {
+                        let conversion_fn = conversion_fn_ident(fn_prefix, idx);
+                        let read = |index: proc_macro2::TokenStream| quote!(Self::#conversion_fn(_turso_mappers::convert::get_value(__turso_row, #index)?)?);
+                        match (row_mapping, default) {
+                            (RowMapping::ByIndex, _) => read(column_positions[idx].clone().expect("only skipped fields have no column position")),
+                            (RowMapping::ByName, None) => read(quote!(__turso_column_indices.get_prefixed_index(__turso_prefix, #column_name)?)),
+                            // A column missing from a by-name mapping gives the default instead of an error
+                            (RowMapping::ByName, Some(default)) => {
+                                let value = read(quote!(__turso_index));
+                                quote! {
+                                    match __turso_column_indices.get_prefixed_index(__turso_prefix, #column_name) {
+                                        Ok(__turso_index) => #value,
+                                        Err(_) => #default,
+                                    }
+                                }
+                            }
                         }
                     }
                 };
-            }
 
-            // Generate code based on the manual implementation for non-Option types
-            let type_path = get_type_path(&f_type);
+                // Validators check each field value as soon as it is produced
+                let value = match &attrs.validate {
+                    Some(validator) => quote! {{
+                        let __turso_value = #value;
+                        if let Err(__turso_err) = #validator(&__turso_value) {
+                            return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{}: {}", #f_name, __turso_err)));
+                        }
+                        __turso_value
+                    }},
+                    None => value,
+                };
 
-            // Handle different types based on the field type
-            match type_path.as_str() {
-                "i64" => quote! {
-                    #f_ident: *row
-                        .get_value(#idx)?
-                        .as_integer()
-                        .ok_or_else(|| crate::TursoMapperError::ConversionError(format!("{} is not an integer", stringify!(#f_ident))))?
-                },
-                "String" => quote! {
-                    #f_ident: row
-                        .get_value(#idx)?
-                        .as_text()
-                        .ok_or_else(|| crate::TursoMapperError::ConversionError(format!("{} is not a string", stringify!(#f_ident))))?
-                        .clone()
-                },
-                "f64" => quote! {
-                    #f_ident: *row
-                        .get_value(#idx)?
-                        .as_real()
-                        .ok_or_else(|| crate::TursoMapperError::ConversionError(format!("{} is not a real", stringify!(#f_ident))))?
-                },
-                "Vec<u8>" => quote! {
-                    #f_ident: row
-                        .get_value(#idx)?
-                        .as_blob()
-                        .ok_or_else(|| crate::TursoMapperError::ConversionError(format!("{} is not a blob", stringify!(#f_ident))))?
-                        .clone()
-                },
-                _ => {
-                    // For unsupported types, generate a compile-time error
-                    let error_msg = format!("Unsupported type: {}", type_path);
-                    quote! {
-                        #f_ident: compile_error!(#error_msg)
-                    }
+                // The context is added to any error produced for the field, so the value is produced in a closure
+                match &attrs.error_context {
+                    Some(context) => quote! {{
+                        #[allow(clippy::redundant_closure_call, clippy::needless_question_mark)]
+                        let __turso_result = (|| -> _turso_mappers::TursoMapperResult<#f_type> { Ok(#value) })();
+                        match __turso_result {
+                            Ok(__turso_value) => __turso_value,
+                            Err(__turso_err) => return Err(__turso_err.with_context(#context)),
+                        }
+                    }},
+                    None => value,
+                }
+            });
+
+            // Named fields are initialized by name, tuple struct fields by position
+            match fields {
+                syn::Fields::Named(_) => {
+                    let f_idents = fields.iter().map(|field| &field.ident);
+                    quote!(#constructor { #(#f_idents: #field_values,)* })
                 }
+                syn::Fields::Unnamed(_) => quote!(#constructor(#(#field_values,)*)),
+                syn::Fields::Unit => constructor.clone(),
             }
         })
         .collect::<Vec<_>>();
 
-    quote! {
-        impl crate::TryFromRowByIndex for #ident {
-            fn try_from_row_by_index(row: turso::Row) -> crate::TursoMapperResult<Self> where Self: Sized {
-                Ok(Self {
-                    #(#field_mappers,)*
-                })
+    Ok(FieldsMapping {
+        conversion_fns,
+        constructions,
+        column_count,
+    })
+}
+
+// Starts the names of the functions a row derive associates with the type, so deriving TryFromRowByIndex and
+// TryFromRowByName separately does not define them twice
+fn row_fn_prefix(row_mappings: &[RowMapping]) -> &'static str {
+    match row_mappings {
+        [RowMapping::ByIndex] => "by_index_",
+        [RowMapping::ByName] => "by_name_",
+        _ => "",
+    }
+}
+
+// The name of the function converting the column value of the field at idx
+fn conversion_fn_ident(fn_prefix: &str, idx: usize) -> Ident {
+    quote::format_ident!("__turso_mappers_convert_{}{}", fn_prefix, idx)
+}
+
+// Checks the attributes of a field and works out where its value comes from
+fn field_source(field: &Field, f_name: &str, attrs: &FieldAttrs, container_attrs: &ContainerAttrs) -> syn::Result<FieldSource> {
+    let f_type = field.ty.clone();
+
+    // Skipped fields are not read from the row at all
+    if let Some(skip_with) = &attrs.skip_with {
+        return Ok(FieldSource::Skipped(quote!(#skip_with)));
+    }
+    if attrs.skip.is_some() {
+        return Ok(FieldSource::Skipped(
+            quote_spanned!(f_type.span()=> <#f_type as ::core::default::Default>::default()),
+        ));
+    }
+
+    if let Some(prefix) = &attrs.prefix
+        && attrs.flatten.is_none()
+    {
+        return Err(syn::Error::new(
+            prefix.span(),
+            "#[turso(prefix = \"...\")] is only supported with #[turso(flatten)]",
+        ));
+    }
+
+    if attrs.flatten.is_some() {
+        return Ok(FieldSource::Flattened);
+    }
+
+    let column = |conversion| FieldSource::Column { conversion, default: None };
+    let coercion = attrs.numeric_coercion(container_attrs);
+
+    // A custom conversion function receives the value as is, NULL included, and its errors are passed through
+    if let Some(with) = &attrs.with {
+        return Ok(column(quote!(#with(__turso_value))));
+    }
+
+    // Check if the field is an Option<T>
+    if let Some(inner_type) = get_option_inner_type(&f_type) {
+        if let Some((_, span)) = &attrs.default {
+            return Err(syn::Error::new(
+                *span,
+                "#[turso(default)] is not supported on Option fields, which are already None for NULL",
+            ));
+        }
+
+        if let Some(span) = attrs.null_as_default {
+            return Err(syn::Error::new(
+                span,
+                "#[turso(null_as_default)] is not supported on Option fields, which are already None for NULL",
+            ));
+        }
+
+        // Option<Option<T>> has no meaning for a single nullable column
+        if let Some(nested_type) = get_option_inner_type(inner_type) {
+            return Err(syn::Error::new_spanned(
+                &f_type,
+                format!("nested Options are not supported, use Option<{}> instead", quote!(#nested_type)),
+            ));
+        }
+
+        let lenient = attrs.lenient.is_some() || (container_attrs.lenient.is_some() && attrs.strict.is_none());
+        return match field_value_mapping(inner_type, f_name, attrs, coercion)? {
+            Some(mapping) if lenient => Ok(column(option_conversion(mapping))),
+            Some(mapping) => Ok(column(strict_option_conversion(f_name, mapping))),
+            None => Err(unsupported_type_error(inner_type, format!("Option<{}>", type_to_string(inner_type)))),
+        };
+    }
+
+    if let Some(span) = attrs.strict {
+        return Err(syn::Error::new(
+            span,
+            "#[turso(strict)] is only supported on Option fields, other fields always fail on a value of the wrong type",
+        ));
+    }
+
+    if let Some(span) = attrs.lenient {
+        return Err(syn::Error::new(
+            span,
+            "#[turso(lenient)] is only supported on Option fields, other fields always fail on a value of the wrong type",
+        ));
+    }
+
+    let Some(mapping) = field_value_mapping(&f_type, f_name, attrs, coercion)? else {
+        return Err(unsupported_type_error(&f_type, type_to_string(&f_type)));
+    };
+
+    let Some((default, _)) = &attrs.default else {
+        let conversion = match attrs.null_as_default {
+            Some(_) => value_match(quote!(__turso_value), f_name, mapping, DefaultValue::Trait.value(&f_type)),
+            None => strict_value_match(quote!(__turso_value), f_name, mapping),
+        };
+        return Ok(column(quote!(Ok(#conversion))));
+    };
+
+    // NULL, or a column missing from a by-name mapping, gives the default instead of an error
+    let default_value = default.value(&f_type);
+    let conversion = value_match(quote!(__turso_value), f_name, mapping, default_value.clone());
+    Ok(FieldSource::Column {
+        conversion: quote!(Ok(#conversion)),
+        default: Some(default_value),
+    })
+}
+
+// The fields of a struct with named fields, for the derives that do not support other kinds of types
+fn named_fields<'a>(ast: &'a DeriveInput, derive_name: &str) -> syn::Result<&'a syn::punctuated::Punctuated<Field, syn::Token![,]>> {
+    match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => Ok(&fields.named),
+        _ => Err(data_kind_error(
+            ast,
+            format!("turso_mappers::{} requires a struct with named fields", derive_name),
+        )),
+    }
+}
+
+// An error about the kind of type the derive is used on, spanning its struct, enum or union keyword and its name
+fn data_kind_error(ast: &DeriveInput, message: impl std::fmt::Display) -> syn::Error {
+    let ident = &ast.ident;
+    let keyword = match &ast.data {
+        syn::Data::Struct(data) => data.struct_token.to_token_stream(),
+        syn::Data::Enum(data) => data.enum_token.to_token_stream(),
+        syn::Data::Union(data) => data.union_token.to_token_stream(),
+    };
+    syn::Error::new_spanned(quote!(#keyword #ident), message)
+}
+
+// Collects the values of the results, or every error combined into one so they are all reported at once
+fn collect_results<T>(results: impl IntoIterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
+    let mut values = vec![];
+    let mut errors: Option<syn::Error> = None;
+    for result in results {
+        match (result, &mut errors) {
+            (Ok(value), _) => values.push(value),
+            (Err(err), Some(errors)) => errors.combine(err),
+            (Err(err), None) => errors = Some(err),
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(values),
+    }
+}
+
+// Tuple struct fields are named by position in error messages, and raw identifiers such as r#type without the r# prefix
+fn field_name(idx: usize, field: &Field) -> String {
+    match &field.ident {
+        Some(f_ident) => f_ident.unraw().to_string(),
+        None => format!("field {}", idx),
+    }
+}
+
+// The column each field is read from by TryFromRowByIndex, as an expression relative to the offset of the struct in
+// the row, and the number of columns the struct reads. A flattened field reads as many columns as its type does, so the
+// positions after it are only known once its COLUMN_COUNT is.
+fn column_layout(fields: &syn::Fields, field_attrs: &[FieldAttrs]) -> syn::Result<(Vec<Option<proc_macro2::TokenStream>>, proc_macro2::TokenStream)> {
+    if field_attrs.iter().all(|attrs| attrs.flatten.is_none()) {
+        let positions = column_positions(field_attrs)?;
+        let column_count = positions.iter().flatten().map(|position| position + 1).max().unwrap_or(0);
+        let positions = positions
+            .into_iter()
+            .map(|position| position.map(|position| quote!(__turso_offset + #position)))
+            .collect();
+        return Ok((positions, quote!(#column_count)));
+    }
+
+    if let Some((_, lit)) = field_attrs.iter().find_map(|attrs| attrs.index.as_ref()) {
+        return Err(syn::Error::new(
+            lit.span(),
+            "#[turso(index = N)] cannot be used in a struct with flattened fields",
+        ));
+    }
+
+    let mut columns = 0usize;
+    let mut flattened = vec![];
+    let positions = fields
+        .iter()
+        .zip(field_attrs)
+        .map(|(field, attrs)| {
+            if attrs.is_skipped() {
+                return None;
             }
+            let position = quote!(__turso_offset + #columns #(+ <#flattened as _turso_mappers::FlattenRowByIndex>::COLUMN_COUNT)*);
+            if attrs.flatten.is_some() {
+                flattened.push(&field.ty);
+            } else {
+                columns += 1;
+            }
+            Some(position)
+        })
+        .collect();
+
+    Ok((
+        positions,
+        quote!(#columns #(+ <#flattened as _turso_mappers::FlattenRowByIndex>::COLUMN_COUNT)*),
+    ))
+}
+
+// The column each field is read from by TryFromRowByIndex. Fields with #[turso(index = N)] read column N, and
+// the remaining fields take the columns not claimed by an index in declaration order.
+// Skipped fields do not read a column, so they do not take a position.
+fn column_positions(field_attrs: &[FieldAttrs]) -> syn::Result<Vec<Option<usize>>> {
+    let mut claimed = std::collections::HashSet::new();
+    for (position, lit) in field_attrs.iter().filter_map(|attrs| attrs.index.as_ref()) {
+        if !claimed.insert(*position) {
+            return Err(syn::Error::new(lit.span(), format!("column index {} is used by more than one field", position)));
         }
     }
+
+    let mut next = 0;
+    Ok(field_attrs
+        .iter()
+        .map(|attrs| match attrs.index {
+            Some((position, _)) => Some(position),
+            None if attrs.is_skipped() => None,
+            None => {
+                let position = (next..).find(|position| !claimed.contains(position)).unwrap_or(next);
+                next = position + 1;
+                Some(position)
+            }
+        })
+        .collect())
 }
 
-// Helper function to extract the type path from a Type
-fn get_type_path(ty: &Type) -> String {
-    match ty {
-        Type::Path(type_path) if !type_path.path.segments.is_empty() => {
-            let segment = &type_path.path.segments[0];
-            let ident = segment.ident.to_string();
-
-            // Handle generic types
-            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                if !args.args.is_empty() {
-                    if let Some(syn::GenericArgument::Type(Type::Path(inner_path))) = args.args.first() {
-                        if !inner_path.path.segments.is_empty() {
-                            let inner_type = inner_path.path.segments[0].ident.to_string();
-                            return format!("{}<{}>", ident, inner_type);
+// Options set on the struct or enum with #[turso(...)]
+#[derive(Default)]
+struct ContainerAttrs {
+    // The case convention applied to the field or variant names, unless renamed individually
+    rename_all: Option<RenameRule>,
+    // The path of the turso_mappers crate, for when it is re-exported or renamed
+    krate: Option<syn::Path>,
+    // Fail on a value of the wrong type for every Option field, which is the default, kept for existing code
+    strict: Option<proc_macro2::Span>,
+    // Map a value of the wrong type to None for every Option field not marked strict
+    lenient: Option<proc_macro2::Span>,
+    // Parse TEXT values for every integer and float field
+    parse_text: Option<proc_macro2::Span>,
+    // With true, read numbers from INTEGER or REAL for every integer and float field, with false only from their own
+    // storage class
+    coerce: Option<(bool, proc_macro2::Span)>,
+    // The table the struct is stored in, emitted as a TABLE constant
+    table: Option<syn::LitStr>,
+    // Fail when a row mapped by index has more or fewer columns than the struct reads
+    deny_extra_columns: Option<proc_macro2::Span>,
+    // The column holding the variant name of an enum mapped from a row
+    tag: Option<syn::LitStr>,
+}
+
+impl ContainerAttrs {
+    fn from_input(ast: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = ContainerAttrs::default();
+
+        for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("turso")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    attrs.rename_all = Some(RenameRule::from_lit(&meta.value()?.parse()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("strict") {
+                    attrs.strict = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("lenient") {
+                    attrs.lenient = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("parse_text") {
+                    attrs.parse_text = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("coerce") {
+                    let enabled = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<syn::LitBool>()?.value
+                    } else {
+                        true
+                    };
+                    attrs.coerce = Some((enabled, meta.path.span()));
+                    Ok(())
+                } else if meta.path.is_ident("tag") {
+                    attrs.tag = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("deny_extra_columns") {
+                    attrs.deny_extra_columns = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("table") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    if !is_table_name(&lit.value()) {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "table must be a plain table name such as \"customer\" or \"main.customer\"",
+                        ));
+                    }
+                    attrs.table = Some(lit);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.krate = Some(lit.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported turso attribute"))
+                }
+            })?;
+        }
+
+        if attrs.strict.is_some()
+            && let Some(span) = attrs.lenient
+        {
+            return Err(syn::Error::new(span, "#[turso(lenient)] cannot be combined with #[turso(strict)]"));
+        }
+
+        if let Some((false, _)) = attrs.coerce
+            && let Some(span) = attrs.parse_text
+        {
+            return Err(syn::Error::new(span, "#[turso(parse_text)] cannot be combined with #[turso(coerce = false)]"));
+        }
+
+        Ok(attrs)
+    }
+
+    // The column a field is stored in, which is its name unless renamed on the field or by rename_all
+    fn column_name(&self, f_name: &str, attrs: &FieldAttrs) -> String {
+        match (&attrs.rename, &self.rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(rule)) => rule.apply(f_name),
+            (None, None) => f_name.to_string(),
+        }
+    }
+
+    // Places the generated impls in an anonymous const that imports turso_mappers as _turso_mappers, which the
+    // generated code uses for every path into the crate, so it compiles wherever the derive is used
+    fn wrap_impls(&self, impls: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let krate = self.krate.clone().unwrap_or_else(|| syn::parse_quote!(::turso_mappers));
+        quote! {
+            const _: () = {
+                use #krate as _turso_mappers;
+                #impls
+            };
+        }
+    }
+}
+
+// Checks for a table name made of identifier characters, optionally qualified with a schema name, so the TABLE
+// constant can be spliced into SQL without quoting
+fn is_table_name(table: &str) -> bool {
+    let parts = table.split('.').collect::<Vec<_>>();
+    parts.len() <= 2
+        && parts.iter().all(|part| {
+            part.chars().next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_') && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+// Options set on a field with #[turso(...)]
+#[derive(Default)]
+struct FieldAttrs {
+    // Reinterpret the bits of the stored i64 as a u64 instead of range checking it
+    bit_cast: Option<proc_macro2::Span>,
+    // Fail instead of producing an infinity when an f64 does not fit in an f32
+    strict_float: Option<proc_macro2::Span>,
+    // With true, read a number from INTEGER or REAL, leaving TEXT to parse_text, and with false only from the field's
+    // own storage class
+    coerce: Option<(bool, proc_macro2::Span)>,
+    // Also parse a TEXT value for an integer or float field
+    parse_text: Option<proc_macro2::Span>,
+    // The TEXT values read as true and false for a bool field, compared ignoring ASCII case
+    bool_text: Option<(String, String, proc_macro2::Span)>,
+    // A function checking the field value once it is produced
+    validate: Option<syn::Path>,
+    // Appended to the conversion errors of the field, to tell where the value came from
+    error_context: Option<syn::LitStr>,
+    // The unit a Duration is stored in, seconds unless set
+    unit: Option<(DurationUnit, proc_macro2::Span)>,
+    // Also accept a BLOB holding UTF-8 text for a String
+    text_from_blob: Option<proc_macro2::Span>,
+    // Also accept any BLOB for a String, replacing invalid UTF-8 sequences with U+FFFD
+    lossy_text: Option<proc_macro2::Span>,
+    // The column name used by TryFromRowByName instead of the field name
+    rename: Option<syn::LitStr>,
+    // The column position used by TryFromRowByIndex instead of the field's place among the other fields
+    index: Option<(usize, syn::LitInt)>,
+    // Leave the field out of the mapping and initialize it with Default::default()
+    skip: Option<proc_macro2::Span>,
+    // Leave the field out of the mapping and initialize it with this expression
+    skip_with: Option<syn::Expr>,
+    // The value used for NULL, or for a column missing from a by-name mapping, instead of failing
+    default: Option<(DefaultValue, proc_macro2::Span)>,
+    // Use Default::default() for NULL only, so a missing column is still an error
+    null_as_default: Option<proc_macro2::Span>,
+    // A function converting the turso::Value into the field type, used instead of the built-in mapping
+    with: Option<syn::Path>,
+    // A supported type the column is read as before converting it to the field type with TryFrom
+    try_from: Option<Type>,
+    // Deserialize the field from JSON held in a TEXT or BLOB column
+    json: Option<proc_macro2::Span>,
+    // Read the field as a struct mapped from the following columns of the same row
+    flatten: Option<proc_macro2::Span>,
+    // Prepended to the column names of a flattened struct mapped by name
+    prefix: Option<syn::LitStr>,
+    // Fail on a value of the wrong type for an Option field, overriding lenient on the struct
+    strict: Option<proc_macro2::Span>,
+    // Map a value of the wrong type to None for an Option field instead of failing
+    lenient: Option<proc_macro2::Span>,
+    // The field holds the primary key of the table
+    primary_key: Option<proc_macro2::Span>,
+    // The column is filled in by the database, such as an INTEGER PRIMARY KEY, so it is left out of INSERT statements
+    auto: Option<proc_macro2::Span>,
+}
+
+// The default given by #[turso(default)] or #[turso(default = "path")]
+enum DefaultValue {
+    Trait,
+    Function(syn::Path),
+}
+
+impl DefaultValue {
+    fn value(&self, ty: &Type) -> proc_macro2::TokenStream {
+        match self {
+            DefaultValue::Trait => quote_spanned!(ty.span()=> <#ty as ::core::default::Default>::default()),
+            DefaultValue::Function(path) => quote!(#path()),
+        }
+    }
+}
+
+// Units accepted by #[turso(unit = "...")] on Duration fields
+#[derive(Clone, Copy)]
+enum DurationUnit {
+    Secs,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl DurationUnit {
+    fn from_lit(lit: &syn::LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "secs" => Ok(DurationUnit::Secs),
+            "millis" => Ok(DurationUnit::Millis),
+            "micros" => Ok(DurationUnit::Micros),
+            "nanos" => Ok(DurationUnit::Nanos),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "unsupported unit, expected \"secs\", \"millis\", \"micros\" or \"nanos\"",
+            )),
+        }
+    }
+}
+
+impl FieldAttrs {
+    fn from_field(field: &Field) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("turso")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("bit_cast") {
+                    attrs.bit_cast = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("validate") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.validate = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("error_context") {
+                    attrs.error_context = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("coerce") {
+                    let enabled = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<syn::LitBool>()?.value
+                    } else {
+                        true
+                    };
+                    attrs.coerce = Some((enabled, meta.path.span()));
+                    Ok(())
+                } else if meta.path.is_ident("parse_text") {
+                    attrs.parse_text = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("bool_text") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.bool_text = Some(parse_bool_text(&lit)?);
+                    Ok(())
+                } else if meta.path.is_ident("strict_float") {
+                    attrs.strict_float = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    attrs.rename = Some(match meta.value()?.parse()? {
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(rename), ..
+                        }) => rename,
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "rename must be a string literal, e.g. #[turso(rename = \"name\")]",
+                            ));
                         }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("index") {
+                    let lit: syn::LitInt = meta.value()?.parse()?;
+                    attrs.index = Some((lit.base10_parse()?, lit));
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    let default = if meta.input.peek(syn::Token![=]) {
+                        let lit: syn::LitStr = meta.value()?.parse()?;
+                        DefaultValue::Function(lit.parse()?)
+                    } else {
+                        DefaultValue::Trait
+                    };
+                    attrs.default = Some((default, meta.path.span()));
+                    Ok(())
+                } else if meta.path.is_ident("null_as_default") {
+                    attrs.null_as_default = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.with = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("try_from") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.try_from = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("json") {
+                    attrs.json = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    attrs.flatten = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("strict") {
+                    attrs.strict = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("lenient") {
+                    attrs.lenient = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("primary_key") {
+                    attrs.primary_key = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("auto") {
+                    attrs.auto = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("prefix") {
+                    attrs.prefix = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("skip_with") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.skip_with = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("text_from_blob") {
+                    attrs.text_from_blob = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("lossy_text") {
+                    attrs.lossy_text = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("unit") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    attrs.unit = Some((DurationUnit::from_lit(&lit)?, lit.span()));
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported turso attribute"))
+                }
+            })?;
+        }
+
+        // PhantomData has no column, so marker fields of generic structs are skipped without an attribute
+        if attrs.skip.is_none() && attrs.skip_with.is_none() && matches!(path_shape(&field.ty), Some((ident, _)) if ident == "PhantomData") {
+            attrs.skip = Some(field.ty.span());
+        }
+
+        if let Some(skip) = attrs.skip
+            && let Some(skip_with) = &attrs.skip_with
+        {
+            let mut err = syn::Error::new(skip_with.span(), "#[turso(skip_with = \"...\")] cannot be combined with #[turso(skip)]");
+            err.combine(syn::Error::new(skip, "#[turso(skip)] given here"));
+            return Err(err);
+        }
+
+        // A skipped field is not read from the row, so attributes saying how to read it are mistakes
+        if attrs.is_skipped() {
+            let read_attrs = [
+                ("index = N", attrs.index.as_ref().map(|(_, lit)| lit.span())),
+                ("rename = \"...\"", attrs.rename.as_ref().map(syn::LitStr::span)),
+                ("with = \"...\"", attrs.with.as_ref().map(Spanned::span)),
+                ("try_from = \"...\"", attrs.try_from.as_ref().map(Spanned::span)),
+                ("json", attrs.json),
+                ("flatten", attrs.flatten),
+                ("default", attrs.default.as_ref().map(|(_, span)| *span)),
+                ("null_as_default", attrs.null_as_default),
+                ("validate = \"...\"", attrs.validate.as_ref().map(Spanned::span)),
+                ("error_context = \"...\"", attrs.error_context.as_ref().map(syn::LitStr::span)),
+                ("strict", attrs.strict),
+                ("lenient", attrs.lenient),
+                ("parse_text", attrs.parse_text),
+                ("coerce", attrs.coerce.map(|(_, span)| span)),
+                ("bool_text = \"...\"", attrs.bool_text.as_ref().map(|(_, _, span)| *span)),
+            ];
+            let errors = read_attrs
+                .into_iter()
+                .filter_map(|(name, span)| span.map(|span| Err(syn::Error::new(span, format!("#[turso({})] cannot be used on a skipped field", name)))));
+            collect_results::<()>(errors)?;
+        }
+
+        if attrs.strict.is_some()
+            && let Some(span) = attrs.lenient
+        {
+            return Err(syn::Error::new(span, "#[turso(lenient)] cannot be combined with #[turso(strict)]"));
+        }
+
+        if let Some((false, _)) = attrs.coerce
+            && let Some(span) = attrs.parse_text
+        {
+            return Err(syn::Error::new(span, "#[turso(parse_text)] cannot be combined with #[turso(coerce = false)]"));
+        }
+
+        // Both read a blob as text, but disagree on invalid UTF-8, so neither silently wins
+        if attrs.text_from_blob.is_some()
+            && let Some(span) = attrs.lossy_text
+        {
+            return Err(syn::Error::new(span, "#[turso(lossy_text)] cannot be combined with #[turso(text_from_blob)]"));
+        }
+
+        Ok(attrs)
+    }
+
+    // How the field reads numbers stored in another storage class. coerce only covers INTEGER and REAL, TEXT is parsed
+    // only with parse_text on the field or the struct. The field's own attributes win over those on the struct, and
+    // coerce = false wins over parse_text.
+    fn numeric_coercion(&self, container_attrs: &ContainerAttrs) -> NumericCoercion {
+        match (self.coerce, self.parse_text, container_attrs.coerce, container_attrs.parse_text) {
+            (Some((false, _)), _, _, _) => NumericCoercion::Exact,
+            (_, Some(_), _, _) | (Some((true, _)), None, _, Some(_)) => NumericCoercion::Text,
+            (Some((true, _)), None, _, None) => NumericCoercion::Numeric,
+            (None, None, Some((false, _)), _) => NumericCoercion::Exact,
+            (None, None, _, Some(_)) => NumericCoercion::Text,
+            (None, None, _, None) => NumericCoercion::Numeric,
+        }
+    }
+
+    fn is_skipped(&self) -> bool {
+        self.skip.is_some() || self.skip_with.is_some()
+    }
+}
+
+// Splits the value of #[turso(bool_text = "Y/N")] into the text read as true and the text read as false
+fn parse_bool_text(lit: &syn::LitStr) -> syn::Result<(String, String, proc_macro2::Span)> {
+    match lit.value().split('/').collect::<Vec<_>>().as_slice() {
+        [truthy, falsy] if !truthy.is_empty() && !falsy.is_empty() && !truthy.eq_ignore_ascii_case(falsy) => {
+            Ok((truthy.to_string(), falsy.to_string(), lit.span()))
+        }
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "bool_text must be the text for true and the text for false separated by a slash, e.g. \"Y/N\" or \"true/false\"",
+        )),
+    }
+}
+
+// The storage classes an integer or float field reads a number from
+#[derive(Clone, Copy)]
+enum NumericCoercion {
+    // Only the storage class of the field, INTEGER or REAL
+    Exact,
+    // INTEGER or REAL, as long as the value converts exactly, which is the default
+    Numeric,
+    // INTEGER, REAL or TEXT holding a number
+    Text,
+}
+
+// Describes how a supported field type is read from a turso::Value
+struct ValueMapping {
+    // The storage class named in the error when the value has a different type, e.g. "an integer"
+    expected: &'static str,
+    // Match arms over turso::Value, each pattern binding `value` and each expression producing the field value
+    arms: Vec<(proc_macro2::TokenStream, proc_macro2::TokenStream)>,
+}
+
+// Returns the mapping for a field, which reads the #[turso(try_from = "...")] type and converts it with TryFrom when set.
+// coercion sets the storage classes a numeric type is read from, as worked out from the field and struct attributes.
+fn field_value_mapping(ty: &Type, f_name: &str, attrs: &FieldAttrs, coercion: NumericCoercion) -> syn::Result<Option<ValueMapping>> {
+    let Some(source) = &attrs.try_from else {
+        return value_mapping(ty, f_name, attrs, coercion);
+    };
+
+    let Some(ValueMapping { expected, arms }) = value_mapping(source, f_name, attrs, coercion)? else {
+        return Err(syn::Error::new_spanned(
+            source,
+            format!("Unsupported try_from type: {}", type_to_string(source)),
+        ));
+    };
+
+    let arms = arms
+        .into_iter()
+        .map(|(pattern, expression)| {
+            let expression = quote! {
+                match <#ty as ::core::convert::TryFrom<#source>>::try_from(#expression) {
+                    Ok(value) => value,
+                    Err(err) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{}: {}", #f_name, err))),
+                }
+            };
+            (pattern, expression)
+        })
+        .collect();
+
+    Ok(Some(ValueMapping { expected, arms }))
+}
+
+// Returns the mapping for a supported type, or None if the type is not supported
+fn value_mapping(ty: &Type, f_name: &str, attrs: &FieldAttrs, coercion: NumericCoercion) -> syn::Result<Option<ValueMapping>> {
+    if let Some((_, span)) = attrs.coerce
+        && !path_shape(ty).is_some_and(|(ident, args)| is_numeric(&ident.to_string(), &args))
+    {
+        return Err(syn::Error::new(span, "#[turso(coerce)] is only supported on integer and float fields"));
+    }
+
+    if let Some(span) = attrs.parse_text
+        && !path_shape(ty).is_some_and(|(ident, args)| is_numeric(&ident.to_string(), &args))
+    {
+        return Err(syn::Error::new(span, "#[turso(parse_text)] is only supported on integer and float fields"));
+    }
+
+    if let Some(span) = attrs.bit_cast
+        && !is_named(ty, "u64")
+    {
+        return Err(syn::Error::new(span, "#[turso(bit_cast)] is only supported on u64 fields"));
+    }
+
+    if let Some(span) = attrs.strict_float
+        && !is_named(ty, "f32")
+    {
+        return Err(syn::Error::new(span, "#[turso(strict_float)] is only supported on f32 fields"));
+    }
+
+    if let Some(span) = attrs.text_from_blob
+        && !is_named(ty, "String")
+    {
+        return Err(syn::Error::new(span, "#[turso(text_from_blob)] is only supported on String fields"));
+    }
+
+    if let Some(span) = attrs.lossy_text
+        && !is_named(ty, "String")
+    {
+        return Err(syn::Error::new(span, "#[turso(lossy_text)] is only supported on String fields"));
+    }
+
+    if let Some((_, _, span)) = attrs.bool_text
+        && !is_named(ty, "bool")
+    {
+        return Err(syn::Error::new(span, "#[turso(bool_text = \"...\")] is only supported on bool fields"));
+    }
+
+    if let Some((_, span)) = attrs.unit
+        && !(is_named(ty, "Duration") && is_bare_or_in(ty, STD_TIME))
+    {
+        return Err(syn::Error::new(span, "#[turso(unit = \"...\")] is only supported on Duration fields"));
+    }
+
+    // Fixed-size blobs, e.g. [u8; 32]
+    if let Type::Array(array) = ty
+        && is_named(&array.elem, "u8")
+    {
+        let len = &array.len;
+        return Ok(Some(ValueMapping {
+            expected: "a blob",
+            arms: vec![(
+                quote!(_turso_mappers::turso::Value::Blob(value)),
+                quote! {
+                    match <[u8; #len] as ::core::convert::TryFrom<::std::vec::Vec<u8>>>::try_from(value) {
+                        Ok(value) => value,
+                        Err(value) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must be {} bytes, got {}", #f_name, #len, value.len()))),
                     }
+                },
+            )],
+        }));
+    }
+
+    // serde_json::Value, or any serde deserializable type with #[turso(json)]
+    #[cfg(feature = "json")]
+    if attrs.json.is_some() || matches!(ty, Type::Path(type_path) if is_json_value_path(&type_path.path)) {
+        return Ok(Some(ValueMapping {
+            expected: "a string or a blob",
+            arms: vec![
+                (
+                    quote!(_turso_mappers::turso::Value::Text(value)),
+                    quote!(_turso_mappers::convert::json::json_from_text::<#ty>(&value, #f_name)?),
+                ),
+                (
+                    quote!(_turso_mappers::turso::Value::Blob(value)),
+                    quote!(_turso_mappers::convert::json::json_from_blob::<#ty>(&value, #f_name)?),
+                ),
+            ],
+        }));
+    }
+
+    #[cfg(not(feature = "json"))]
+    if let Some(span) = attrs.json {
+        return Err(syn::Error::new(span, "#[turso(json)] requires the json feature of turso-mappers"));
+    }
+
+    // Everything else is recognized by the last segment of its path and that segment's type arguments
+    let Some((ident, args)) = path_shape(ty) else {
+        return Ok(None);
+    };
+    let name = ident.to_string();
+    let name = name.as_str();
+
+    #[cfg(feature = "chrono")]
+    if is_bare_or_in(ty, &[&["chrono"], &["chrono", "naive"]])
+        && let Some(mapping) = chrono_mapping(name, &args, f_name)
+    {
+        return Ok(Some(mapping));
+    }
+
+    #[cfg(feature = "jiff")]
+    if is_bare_or_in(ty, &[&["jiff"]])
+        && let Some(mapping) = jiff_mapping(name, &args, f_name)
+    {
+        return Ok(Some(mapping));
+    }
+
+    #[cfg(feature = "time")]
+    if is_bare_or_in(ty, &[&["time"]])
+        && let Some(mapping) = time_mapping(name, &args, f_name)
+    {
+        return Ok(Some(mapping));
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    if name == "BigDecimal" && args.is_empty() && is_bare_or_in(ty, &[&["bigdecimal"]]) {
+        return Ok(Some(ValueMapping {
+            expected: "a string, an integer or a real",
+            arms: vec![
+                (
+                    quote!(_turso_mappers::turso::Value::Text(value)),
+                    quote!(_turso_mappers::convert::bigdecimal::big_decimal_from_text(&value, #f_name)?),
+                ),
+                (
+                    quote!(_turso_mappers::turso::Value::Integer(value)),
+                    quote!(_turso_mappers::convert::bigdecimal::big_decimal_from_integer(value)),
+                ),
+                (
+                    quote!(_turso_mappers::turso::Value::Real(value)),
+                    quote!(_turso_mappers::convert::bigdecimal::big_decimal_from_real(value, #f_name)?),
+                ),
+            ],
+        }));
+    }
+
+    #[cfg(feature = "semver")]
+    if name == "Version" && args.is_empty() && is_bare_or_in(ty, &[&["semver"]]) {
+        return Ok(Some(ValueMapping {
+            expected: "a string",
+            arms: vec![(
+                quote!(_turso_mappers::turso::Value::Text(value)),
+                quote!(_turso_mappers::convert::semver::version_from_text(&value, #f_name)?),
+            )],
+        }));
+    }
+
+    #[cfg(feature = "uuid")]
+    if name == "Uuid" && args.is_empty() && is_bare_or_in(ty, &[&["uuid"]]) {
+        return Ok(Some(ValueMapping {
+            expected: "a blob or a string",
+            arms: vec![
+                (
+                    quote!(_turso_mappers::turso::Value::Blob(value)),
+                    quote!(_turso_mappers::convert::uuid::uuid_from_blob(&value, #f_name)?),
+                ),
+                (
+                    quote!(_turso_mappers::turso::Value::Text(value)),
+                    quote!(_turso_mappers::convert::uuid::uuid_from_text(&value, #f_name)?),
+                ),
+            ],
+        }));
+    }
+
+    if attrs.text_from_blob.is_some() || attrs.lossy_text.is_some() {
+        let blob = if attrs.lossy_text.is_some() {
+            // Valid UTF-8 is taken without copying, the rest gets replacement characters instead of failing
+            quote! {
+                match ::std::string::String::from_utf8(value) {
+                    Ok(value) => value,
+                    Err(err) => ::std::string::String::from_utf8_lossy(err.as_bytes()).into_owned(),
+                }
+            }
+        } else {
+            quote! {
+                match ::std::string::String::from_utf8(value) {
+                    Ok(value) => value,
+                    Err(err) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} is not valid UTF-8: {}", #f_name, err))),
                 }
             }
-            ident
+        };
+
+        return Ok(Some(ValueMapping {
+            expected: "a string or a blob",
+            arms: vec![
+                (quote!(_turso_mappers::turso::Value::Text(value)), quote!(value)),
+                (quote!(_turso_mappers::turso::Value::Blob(value)), blob),
+            ],
+        }));
+    }
+
+    let (expected, pattern, expression) = match (name, args.as_slice()) {
+        ("i64", []) => ("an integer", quote!(_turso_mappers::turso::Value::Integer(value)), quote!(value)),
+        ("String", []) => ("a string", quote!(_turso_mappers::turso::Value::Text(value)), quote!(value)),
+        ("Box", [inner]) if is_named(inner, "str") => ("a string", quote!(_turso_mappers::turso::Value::Text(value)), quote!(value.into_boxed_str())),
+        ("Cow", [inner]) if is_named(inner, "str") => (
+            "a string",
+            quote!(_turso_mappers::turso::Value::Text(value)),
+            quote!(::std::borrow::Cow::Owned(value)),
+        ),
+        ("Arc", [inner]) if is_named(inner, "str") => (
+            "a string",
+            quote!(_turso_mappers::turso::Value::Text(value)),
+            quote!(::std::sync::Arc::<str>::from(value)),
+        ),
+        ("char", []) => (
+            "a string",
+            quote!(_turso_mappers::turso::Value::Text(value)),
+            quote! {
+                {
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must contain exactly one character, got {:?}", #f_name, value))),
+                    }
+                }
+            },
+        ),
+        ("PathBuf", []) => (
+            "a string",
+            quote!(_turso_mappers::turso::Value::Text(value)),
+            quote!(::std::path::PathBuf::from(value)),
+        ),
+        ("IpAddr" | "Ipv4Addr" | "Ipv6Addr" | "SocketAddr", []) => (
+            "a string",
+            quote!(_turso_mappers::turso::Value::Text(value)),
+            parsed_network_address(name, f_name),
+        ),
+        ("Duration", []) if is_bare_or_in(ty, STD_TIME) => return Ok(Some(duration_mapping(attrs.unit.map_or(DurationUnit::Secs, |(unit, _)| unit), f_name))),
+        ("f64", []) => ("a real", quote!(_turso_mappers::turso::Value::Real(value)), quote!(value)),
+        ("f32", []) => (
+            "a real",
+            quote!(_turso_mappers::turso::Value::Real(value)),
+            if attrs.strict_float.is_some() {
+                quote! {
+                    match value as f32 {
+                        narrowed if narrowed.is_infinite() && value.is_finite() => {
+                            return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} value {} is out of range for f32", #f_name, value)));
+                        }
+                        narrowed => narrowed,
+                    }
+                }
+            } else {
+                quote!(value as f32)
+            },
+        ),
+        ("Vec", [inner]) if is_named(inner, "u8") => ("a blob", quote!(_turso_mappers::turso::Value::Blob(value)), quote!(value)),
+        ("Box", [Type::Slice(slice)]) if is_named(&slice.elem, "u8") => {
+            ("a blob", quote!(_turso_mappers::turso::Value::Blob(value)), quote!(value.into_boxed_slice()))
+        }
+        // bytes::Bytes takes ownership of the Vec<u8> without copying
+        #[cfg(feature = "bytes")]
+        ("Bytes", []) if is_bare_or_in(ty, &[&["bytes"]]) => (
+            "a blob",
+            quote!(_turso_mappers::turso::Value::Blob(value)),
+            quote!(::core::convert::From::from(value)),
+        ),
+        ("bool", []) => {
+            let integer = quote! {
+                match value {
+                    0 => false,
+                    1 => true,
+                    other => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must be 0 or 1 to map to a bool, got {}", #f_name, other))),
+                }
+            };
+            let Some((truthy, falsy, _)) = &attrs.bool_text else {
+                return Ok(Some(ValueMapping {
+                    expected: "an integer",
+                    arms: vec![(quote!(_turso_mappers::turso::Value::Integer(value)), integer)],
+                }));
+            };
+
+            // Legacy schemas storing flags as text, such as 'Y' and 'N', still accept 0 and 1
+            return Ok(Some(ValueMapping {
+                expected: "an integer or a string",
+                arms: vec![
+                    (quote!(_turso_mappers::turso::Value::Integer(value)), integer),
+                    (
+                        quote!(_turso_mappers::turso::Value::Text(value)),
+                        quote! {
+                            if value.eq_ignore_ascii_case(#truthy) {
+                                true
+                            } else if value.eq_ignore_ascii_case(#falsy) {
+                                false
+                            } else {
+                                return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must be {:?} or {:?} to map to a bool, got {:?}", #f_name, #truthy, #falsy, value)));
+                            }
+                        },
+                    ),
+                ],
+            }));
         }
-        _ => "unknown".to_string(),
+        ("i32" | "i16" | "i8" | "isize" | "u64" | "u32" | "u16" | "u8" | "usize", []) => (
+            "an integer",
+            quote!(_turso_mappers::turso::Value::Integer(value)),
+            if attrs.bit_cast.is_some() {
+                quote!(value as u64)
+            } else {
+                checked_integer_conversion(name, f_name)
+            },
+        ),
+        // Other std containers have no TryFromValue impl, so they get the unsupported type error here rather than an
+        // unsatisfied trait bound from the fallback below
+        ("Vec", [_]) | ("Box" | "Arc" | "Rc" | "Cow", [Type::Slice(_)]) => return Ok(None),
+        _ => match non_zero_integer_type(name, &args) {
+            Some(integer_type) => {
+                let integer = match integer_type {
+                    "i64" => quote!(value),
+                    _ => checked_integer_conversion(integer_type, f_name),
+                };
+
+                (
+                    "an integer",
+                    quote!(_turso_mappers::turso::Value::Integer(value)),
+                    quote! {
+                        match ::core::num::NonZero::new(#integer) {
+                            Some(value) => value,
+                            None => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must not be zero", #f_name))),
+                        }
+                    },
+                )
+            }
+            None if matches!(ty, Type::Path(_)) => return Ok(Some(try_from_value_mapping(ty, f_name))),
+            None => return Ok(None),
+        },
+    };
+
+    // SQLite returns a whole number stored in a REAL column as an INTEGER, and an expression such as `SUM(x) / 2` can
+    // return a whole REAL for an integer column, so numeric fields accept both storage classes unless told otherwise
+    if !is_numeric(name, &args) || matches!(coercion, NumericCoercion::Exact) {
+        return Ok(Some(ValueMapping {
+            expected,
+            arms: vec![(pattern, expression)],
+        }));
+    }
+
+    // The other numeric storage class is accepted as long as the value converts exactly
+    let coerced = match name {
+        "f64" => quote!(_turso_mappers::convert::numeric::real_from_integer(value, #f_name)?),
+        "f32" => quote!(_turso_mappers::convert::numeric::f32_from_integer(value, #f_name)?),
+        _ => quote! {{
+            let value = _turso_mappers::convert::numeric::integer_from_real(value, #f_name)?;
+            #expression
+        }},
+    };
+    let (coerced_expected, coerced_pattern) = match name {
+        "f64" | "f32" => ("a real or an integer", quote!(_turso_mappers::turso::Value::Integer(value))),
+        _ => ("an integer or a real", quote!(_turso_mappers::turso::Value::Real(value))),
+    };
+
+    if matches!(coercion, NumericCoercion::Numeric) {
+        return Ok(Some(ValueMapping {
+            expected: coerced_expected,
+            arms: vec![(pattern, expression), (coerced_pattern, coerced)],
+        }));
     }
+
+    // Text is parsed as the storage class of the field, then converted like a stored value, so range checks still apply
+    let (parsed_expected, parsed_type) = match name {
+        "f64" | "f32" => ("a real, an integer or a numeric string", quote!(f64)),
+        _ => ("an integer, a real or a numeric string", quote!(i64)),
+    };
+    let parsed = quote! {{
+        let value = _turso_mappers::convert::numeric::parse_text::<#parsed_type>(&value, #f_name)?;
+        #expression
+    }};
+
+    Ok(Some(ValueMapping {
+        expected: parsed_expected,
+        arms: vec![
+            (pattern, expression),
+            (coerced_pattern, coerced),
+            (quote!(_turso_mappers::turso::Value::Text(value)), parsed),
+        ],
+    }))
 }
 
-// Helper function to extract the inner type of an Option<T>
-fn get_option_inner_type(ty: &Type) -> Option<String> {
-    match ty {
-        Type::Path(type_path) if !type_path.path.segments.is_empty() => {
-            let segment = &type_path.path.segments[0];
-            let ident = segment.ident.to_string();
-
-            if ident == "Option" {
-                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                    if !args.args.is_empty() {
-                        if let Some(syn::GenericArgument::Type(inner_type)) = args.args.first() {
-                            return Some(get_type_path(inner_type));
-                        }
+// Integer and float types, including the NonZero integers, which accept both numeric storage classes
+fn is_numeric(name: &str, args: &[&Type]) -> bool {
+    matches!(
+        name,
+        "f64" | "f32" | "i64" | "i32" | "i16" | "i8" | "isize" | "u64" | "u32" | "u16" | "u8" | "usize"
+    ) || non_zero_integer_type(name, args).is_some()
+}
+
+// Any other named type is converted through its TryFromValue impl, e.g. an enum deriving TryFromValue
+fn try_from_value_mapping(ty: &Type, f_name: &str) -> ValueMapping {
+    ValueMapping {
+        expected: "a supported value",
+        arms: vec![(
+            quote!(value),
+            quote! {
+                match <#ty as _turso_mappers::TryFromValue>::try_from_value(value) {
+                    Ok(value) => value,
+                    Err(_turso_mappers::TursoMapperError::ConversionError(msg)) => {
+                        return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{}: {}", #f_name, msg)));
                     }
+                    Err(err) => return Err(err),
                 }
+            },
+        )],
+    }
+}
+
+// Maps chrono date and time types from RFC3339, ISO or SQLite formatted text, and date times also from unix seconds
+#[cfg(feature = "chrono")]
+fn chrono_mapping(name: &str, args: &[&Type], f_name: &str) -> Option<ValueMapping> {
+    let (from_text, from_unix) = match (name, args) {
+        ("DateTime", [inner]) if is_named(inner, "Utc") => (quote!(datetime_utc_from_text), Some(quote!(datetime_utc_from_unix))),
+        ("NaiveDateTime", []) => (quote!(naive_datetime_from_text), Some(quote!(naive_datetime_from_unix))),
+        ("NaiveDate", []) => (quote!(naive_date_from_text), None),
+        ("NaiveTime", []) => (quote!(naive_time_from_text), None),
+        _ => return None,
+    };
+
+    let mut arms = vec![(
+        quote!(_turso_mappers::turso::Value::Text(value)),
+        quote!(_turso_mappers::convert::chrono::#from_text(&value, #f_name)?),
+    )];
+
+    if let Some(from_unix) = &from_unix {
+        arms.push((
+            quote!(_turso_mappers::turso::Value::Integer(value)),
+            quote!(_turso_mappers::convert::chrono::#from_unix(value, #f_name)?),
+        ));
+    }
+
+    Some(ValueMapping {
+        expected: if from_unix.is_some() { "a string or an integer" } else { "a string" },
+        arms,
+    })
+}
+
+// Maps jiff timestamps from RFC3339 or Temporal text, or from unix seconds, and zoned date times from Temporal text
+#[cfg(feature = "jiff")]
+fn jiff_mapping(name: &str, args: &[&Type], f_name: &str) -> Option<ValueMapping> {
+    let (from_text, from_unix) = match (name, args) {
+        ("Timestamp", []) => (quote!(timestamp_from_text), Some(quote!(timestamp_from_unix))),
+        ("Zoned", []) => (quote!(zoned_from_text), None),
+        _ => return None,
+    };
+
+    let mut arms = vec![(
+        quote!(_turso_mappers::turso::Value::Text(value)),
+        quote!(_turso_mappers::convert::jiff::#from_text(&value, #f_name)?),
+    )];
+
+    if let Some(from_unix) = &from_unix {
+        arms.push((
+            quote!(_turso_mappers::turso::Value::Integer(value)),
+            quote!(_turso_mappers::convert::jiff::#from_unix(value, #f_name)?),
+        ));
+    }
+
+    Some(ValueMapping {
+        expected: if from_unix.is_some() { "a string or an integer" } else { "a string" },
+        arms,
+    })
+}
+
+// Maps time crate date times from RFC3339 or SQLite formatted text, and offset date times also from unix seconds
+#[cfg(feature = "time")]
+fn time_mapping(name: &str, args: &[&Type], f_name: &str) -> Option<ValueMapping> {
+    let (from_text, from_unix) = match (name, args) {
+        ("OffsetDateTime", []) => (quote!(offset_datetime_from_text), Some(quote!(offset_datetime_from_unix))),
+        ("PrimitiveDateTime", []) => (quote!(primitive_datetime_from_text), None),
+        _ => return None,
+    };
+
+    let mut arms = vec![(
+        quote!(_turso_mappers::turso::Value::Text(value)),
+        quote!(_turso_mappers::convert::time::#from_text(&value, #f_name)?),
+    )];
+
+    if let Some(from_unix) = &from_unix {
+        arms.push((
+            quote!(_turso_mappers::turso::Value::Integer(value)),
+            quote!(_turso_mappers::convert::time::#from_unix(value, #f_name)?),
+        ));
+    }
+
+    Some(ValueMapping {
+        expected: if from_unix.is_some() { "a string or an integer" } else { "a string" },
+        arms,
+    })
+}
+
+// Maps a std::time::Duration from a non-negative INTEGER count of `unit`, or from a REAL number of `unit` with a fractional
+// part, so a REAL is fractional seconds unless the field sets another unit, e.g. 1.5 milliseconds with unit = "millis"
+fn duration_mapping(unit: DurationUnit, f_name: &str) -> ValueMapping {
+    let (from_integer, units_per_sec) = match unit {
+        DurationUnit::Secs => (quote!(from_secs), 1e0),
+        DurationUnit::Millis => (quote!(from_millis), 1e3),
+        DurationUnit::Micros => (quote!(from_micros), 1e6),
+        DurationUnit::Nanos => (quote!(from_nanos), 1e9),
+    };
+
+    ValueMapping {
+        expected: "an integer or a real",
+        arms: vec![
+            (
+                quote!(_turso_mappers::turso::Value::Integer(value)),
+                quote! {
+                    match u64::try_from(value) {
+                        Ok(count) => ::std::time::Duration::#from_integer(count),
+                        Err(_) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must not be negative, got {}", #f_name, value))),
+                    }
+                },
+            ),
+            (
+                quote!(_turso_mappers::turso::Value::Real(value)),
+                quote! {{
+                    if value < 0.0 {
+                        return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} must not be negative, got {}", #f_name, value)));
+                    }
+                    match ::std::time::Duration::try_from_secs_f64(value / #units_per_sec) {
+                        Ok(duration) => duration,
+                        Err(_) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} value {} is out of range for a duration", #f_name, value))),
+                    }
+                }},
+            ),
+        ],
+    }
+}
+
+// Returns the underlying integer type of a std::num::NonZero* type, e.g. "u32" for NonZeroU32 or NonZero<u32>
+fn non_zero_integer_type(name: &str, args: &[&Type]) -> Option<&'static str> {
+    const INTEGER_TYPES: [&str; 10] = ["i64", "i32", "i16", "i8", "isize", "u64", "u32", "u16", "u8", "usize"];
+
+    match (name, args) {
+        ("NonZero", [inner]) => INTEGER_TYPES.into_iter().find(|integer_type| is_named(inner, integer_type)),
+        (_, []) => {
+            let integer_type = name.strip_prefix("NonZero")?.to_lowercase();
+            INTEGER_TYPES.into_iter().find(|supported| *supported == integer_type)
+        }
+        _ => None,
+    }
+}
+
+// Generates a checked conversion of the i64 `value` into a narrower integer type
+fn checked_integer_conversion(integer_type: &str, f_name: &str) -> proc_macro2::TokenStream {
+    let target = Ident::new(integer_type, proc_macro2::Span::call_site());
+
+    quote! {
+        match <#target as ::core::convert::TryFrom<i64>>::try_from(value) {
+            Ok(value) => value,
+            Err(_) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} value {} is out of range for {}", #f_name, value, #integer_type))),
+        }
+    }
+}
+
+// Generates a FromStr parse of the text value into one of the std::net address types
+fn parsed_network_address(address_type: &str, f_name: &str) -> proc_macro2::TokenStream {
+    let target = Ident::new(address_type, proc_macro2::Span::call_site());
+    let description = match address_type {
+        "SocketAddr" => "a socket address",
+        _ => "an IP address",
+    };
+
+    quote! {
+        match value.parse::<::std::net::#target>() {
+            Ok(address) => address,
+            Err(_) => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} could not be parsed as {}: {:?}", #f_name, #description, value))),
+        }
+    }
+}
+
+// Generates a match converting a turso::Value, which fails on NULL or on a value of the wrong type
+fn strict_value_match(value: proc_macro2::TokenStream, f_name: &str, mapping: ValueMapping) -> proc_macro2::TokenStream {
+    let null_value = quote!(return Err(_turso_mappers::TursoMapperError::NullValue(#f_name.to_string())));
+    value_match(value, f_name, mapping, null_value)
+}
+
+// Generates a match converting a turso::Value, which gives null_value on NULL and fails on a value of the wrong type
+fn value_match(value: proc_macro2::TokenStream, f_name: &str, mapping: ValueMapping, null_value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ValueMapping { expected, arms } = mapping;
+    let (patterns, expressions): (Vec<_>, Vec<_>) = arms.into_iter().unzip();
+
+    quote! {
+        match #value {
+            _turso_mappers::turso::Value::Null => #null_value,
+            #(#patterns => #expressions,)*
+            #[allow(unreachable_patterns)]
+            _ => return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} is not {}", #f_name, #expected))),
+        }
+    }
+}
+
+// Generates the conversion for an Option<T> field marked lenient, which maps NULL or a value of the wrong type to None
+fn option_conversion(mapping: ValueMapping) -> proc_macro2::TokenStream {
+    let (patterns, expressions): (Vec<_>, Vec<_>) = mapping.arms.into_iter().unzip();
+
+    quote! {
+        Ok(match __turso_value {
+            _turso_mappers::turso::Value::Null => None,
+            #(#patterns => Some(#expressions),)*
+            #[allow(unreachable_patterns)]
+            _ => None,
+        })
+    }
+}
+
+// Generates the conversion for an Option<T> field, which maps only NULL to None and fails on a value of the wrong
+// type
+fn strict_option_conversion(f_name: &str, mapping: ValueMapping) -> proc_macro2::TokenStream {
+    let ValueMapping { expected, arms } = mapping;
+    let (patterns, expressions): (Vec<_>, Vec<_>) = arms.into_iter().unzip();
+
+    quote! {
+        Ok(match __turso_value {
+            _turso_mappers::turso::Value::Null => None,
+            #(#patterns => Some(#expressions),)*
+            #[allow(unreachable_patterns)]
+            __turso_other => {
+                let __turso_actual = _turso_mappers::convert::storage_class(&__turso_other);
+                return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} is not {}, got {}", #f_name, #expected, __turso_actual)));
+            }
+        })
+    }
+}
+
+// Splits a path type into the ident of its last segment and that segment's type arguments, so std::vec::Vec<u8>
+// gives (Vec, [u8]) and Cow<'a, str> gives (Cow, [str]), or returns None for any other kind of type
+fn path_shape(ty: &Type) -> Option<(&Ident, Vec<&Type>)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+
+    let segment = type_path.path.segments.last()?;
+    let args = match &segment.arguments {
+        syn::PathArguments::None => vec![],
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(inner_type) => Some(inner_type),
+                _ => None,
+            })
+            .collect(),
+        syn::PathArguments::Parenthesized(_) => return None,
+    };
+
+    Some((&segment.ident, args))
+}
+
+// The modules std::time::Duration can be named through
+const STD_TIME: &[&[&str]] = &[&["std", "time"], &["core", "time"]];
+
+// Checks that a path type is written as just its name, or qualified with one of `modules`, so that types named like a
+// supported type in other crates or modules, such as chrono::Duration, are left to their TryFromValue impl
+fn is_bare_or_in(ty: &Type, modules: &[&[&str]]) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let leading = type_path
+        .path
+        .segments
+        .iter()
+        .rev()
+        .skip(1)
+        .rev()
+        .map(|segment| &segment.ident)
+        .collect::<Vec<_>>();
+
+    (leading.is_empty() && type_path.path.leading_colon.is_none())
+        || modules
+            .iter()
+            .any(|module| module.len() == leading.len() && module.iter().zip(&leading).all(|(name, ident)| *ident == name))
+}
+
+// Checks for a path type without type arguments whose last segment is `name`, e.g. u8 or std::primitive::u8
+fn is_named(ty: &Type, name: &str) -> bool {
+    matches!(path_shape(ty), Some((ident, args)) if ident == name && args.is_empty())
+}
+
+// Renders a type as written for error messages, e.g. HashMap<String, Vec<u8>>, keeping only the spaces
+// quote puts between words (as in &'static str), after lifetimes (as in &'a [u8]) and after separators
+fn type_to_string(ty: &Type) -> String {
+    let tokens = quote!(#ty).to_string().chars().collect::<Vec<_>>();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let follows_lifetime = |i: usize| {
+        let start = tokens[..i].iter().rposition(|&c| !is_word(c));
+        start.is_some_and(|start| start + 1 < i && tokens[start] == '\'')
+    };
+
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            c != ' '
+                || (i > 0
+                    && i + 1 < tokens.len()
+                    && ((is_word(tokens[i - 1]) && is_word(tokens[i + 1])) || follows_lifetime(i) || matches!(tokens[i - 1], ',' | ';')))
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+// Lists the supported field types for the error on an unsupported one
+const SUPPORTED_TYPES: &str = "integers, f64, f32, bool, String, char, Vec<u8>, [u8; N], Duration, PathBuf, IP and socket addresses, \
+                               types implementing TryFromValue, or an Option of any of these";
+
+// The error for a field type that has no mapping, reported at the type
+fn unsupported_type_error(ty: &Type, rendered: String) -> syn::Error {
+    syn::Error::new_spanned(ty, format!("Unsupported type: {}, expected {}", rendered, SUPPORTED_TYPES))
+}
+
+// Helper function to check for serde_json::Value, which is only matched fully qualified to avoid confusion with turso::Value
+#[cfg(feature = "json")]
+fn is_json_value_path(path: &syn::Path) -> bool {
+    let idents = path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    matches!(idents.iter().map(String::as_str).collect::<Vec<_>>().as_slice(), ["serde_json", "Value"])
+}
+
+// Helper function to extract the inner type of an Option<T>, which is also matched when written as std::option::Option or core::option::Option
+fn get_option_inner_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() && is_option_path(&type_path.path) => {
+            let segment = type_path.path.segments.last().unwrap();
+
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+                && let Some(syn::GenericArgument::Type(inner_type)) = args.args.first()
+            {
+                return Some(inner_type);
             }
             None
         }
@@ -174,8 +1765,270 @@ fn get_option_inner_type(ty: &Type) -> Option<String> {
     }
 }
 
-#[proc_macro_derive(TryFromRowByIndex)]
+fn is_option_path(path: &syn::Path) -> bool {
+    let idents = path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+    matches!(
+        idents.iter().map(String::as_str).collect::<Vec<_>>().as_slice(),
+        ["Option"] | ["std" | "core", "option", "Option"]
+    )
+}
+
+/// Derives `turso_mappers::TryFromRowByIndex`, mapping the columns of a row to the struct fields in declaration order.
+///
+/// Narrower integer fields (`i32`, `u16`, `usize`, ...) are range checked, so a count that is negative
+/// or does not fit on the target platform is reported as a `ConversionError` naming the field.
+///
+/// Use `#[turso(index = N)]` to read a field from column `N` instead. Fields without an index take the
+/// columns not claimed by one, in declaration order, and two fields claiming the same index is a compile error.
+///
+/// Fields marked `#[turso(skip)]` are not read from the row and do not take a column. They are initialized with
+/// `Default::default()`, or with the expression given by `#[turso(skip_with = "...")]`.
+///
+/// Generic structs are supported, with the generated impls carrying their parameters and where clauses. `PhantomData`
+/// fields, such as the marker of a struct generic over a table type, are skipped without an attribute.
+///
+/// Fields marked `#[turso(default)]` use `Default::default()` when the column is NULL, and
+/// `#[turso(default = "path")]` calls the named function instead. A value of the wrong type is still an error.
+/// `#[turso(null_as_default)]` uses `Default::default()` for NULL without also covering a missing column, for NOT NULL
+/// columns holding legacy NULLs. Combined with `default = "path"`, the function gives the value as usual.
+///
+/// A field whose type implements `TryFrom` for a supported type can be read as that type and converted with
+/// `#[turso(try_from = "String")]`, reporting a failed conversion as a `ConversionError` with the error's message.
+///
+/// With the `json` feature, `#[turso(json)]` deserializes a field of any `serde::Deserialize` type from JSON held
+/// in a TEXT or BLOB column.
+///
+/// A field whose type also derives `TryFromRowByIndex` can be marked `#[turso(flatten)]` to read it from the next
+/// columns of the same row, as many as the fields of that type, so a query joining two tables can be mapped to a
+/// struct holding both.
+///
+/// For columns needing a bespoke conversion, `#[turso(with = "path")]` calls a
+/// `fn(turso::Value) -> TursoMapperResult<T>` with the column value, NULL included, and returns its errors unchanged.
+///
+/// SQLite can return an INTEGER from a REAL column for whole numbers, so float fields also accept integers, failing if
+/// the value has no exact float equivalent. Integer fields likewise accept a REAL holding a whole number in the `i64`
+/// range, and report any other real, such as `42.5`, as a `ConversionError`.
+///
+/// bool fields are read from 0 or 1. `#[turso(bool_text = "Y/N")]` also reads the two given texts, ignoring ASCII case,
+/// so `"true/false"`, `"t/f"` or `"yes/no"` suit other legacy columns, and reports any other text as a `ConversionError`.
+///
+/// Numbers stored as TEXT are rejected unless the field, or the whole struct, is marked `#[turso(parse_text)]`, which
+/// parses the text with `FromStr` and reports text that is not a number as a `ConversionError` showing it.
+///
+/// `#[turso(coerce)]` on the struct reads every integer and float field from INTEGER or REAL, for queries where
+/// SQLite's type affinity decides the storage class, and combines with `#[turso(parse_text)]` to read numeric TEXT as
+/// well. `#[turso(coerce = false)]` reads a field only from its own storage class instead. An attribute on a field wins
+/// over one on the struct, so a field can opt out of a coerced struct, or into parsing text in a `coerce = false` struct.
+///
+/// `Option` fields map NULL to `None` and report a value of the wrong type as a `ConversionError`. Mark a field
+/// `#[turso(lenient)]`, or the whole struct, to map a value of the wrong type to `None` as well. `#[turso(strict)]` on a
+/// field opts it back out of a lenient struct.
+///
+/// `#[turso(validate = "path")]` calls a `fn(&T) -> Result<(), E>`, with `E` being any `Display` type such as `String`,
+/// on the field value, and reports an error as a `ConversionError` naming the field.
+///
+/// `#[turso(deny_extra_columns)]` on the struct makes the mapping fail with an `ArityMismatch` error when the row has
+/// more or fewer columns than the struct reads, so a column added to the middle of a query cannot shift the fields.
+///
+/// `#[turso(error_context = "...")]` appends the given text to the `ConversionError` and `NullValue` errors of a field,
+/// to tell which query or report the value came from.
+///
+/// `#[turso(table = "customer")]` on the struct adds a `TABLE` constant holding the table name. When a field is also
+/// marked `#[turso(primary_key)]`, an async `find_by_id(conn, &id)` selects the row with that key, returning `None` if
+/// there is none and a `TooManyRows` error if several rows match. Derive `TryFromRow` instead of both row derives on a
+/// struct with a table, as each of them adds the `TABLE` constant.
+///
+/// Enums whose variants have named fields or no fields can be derived with `#[turso(tag = "kind")]`, mapping a row to the
+/// variant named by its tag column, honoring `rename` on the variants and `rename_all` on the enum. By index, the tag
+/// is the first column and the fields of each variant are read from the columns after it. An unknown tag is a
+/// `ConversionError` listing the known ones, unless a unit variant is marked `#[turso(other)]` to catch it.
+///
+/// The generated code refers to the runtime crate as `::turso_mappers`, and reaches turso through it, so a renamed
+/// turso dependency or a local module named `turso` does not affect it. When turso-mappers is re-exported from another
+/// crate or renamed in Cargo.toml, set its path with `#[turso(crate = "our_db::mappers")]` on the struct.
+///
+/// ```ignore
+/// #[derive(TryFromRowByIndex)]
+/// struct CustomerOrderCount {
+///     name: String,
+///     order_count: usize,
+/// }
+///
+/// let counts = conn
+///     .query_as_by_index::<CustomerOrderCount>("SELECT name, count(*) AS n FROM orders GROUP BY name;", ())
+///     .await?;
+/// ```
+#[proc_macro_derive(TryFromRowByIndex, attributes(turso))]
 pub fn try_from_row_derive(input: TokenStream) -> TokenStream {
-    let ast: DeriveInput = syn::parse(input).unwrap();
-    impl_try_from_row_by_index(ast).into()
-}
\ No newline at end of file
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    impl_try_from_row(ast, &[RowMapping::ByIndex])
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `turso_mappers::TryFromRowByName`, mapping each struct field from the column with the same name.
+///
+/// Use `#[turso(rename = "...")]` on a field when the column has a different name, or
+/// `#[turso(rename_all = "camelCase")]` on the struct to convert every field name with one of serde's case
+/// conventions. Fields support the same types and attributes as with `TryFromRowByIndex`, which ignores `rename`
+/// and `rename_all`. A field marked `#[turso(default)]` also uses its default when the column is missing from the
+/// query.
+///
+/// Enums with a `#[turso(tag = "kind")]` column are supported as with `TryFromRowByIndex`, reading the tag and the
+/// fields of the variant by name.
+///
+/// A field marked `#[turso(flatten)]` is mapped by name from the same row, and `#[turso(flatten, prefix = "addr_")]`
+/// prepends the prefix to the column names of its fields, so `street` is read from the `addr_street` column.
+///
+/// `#[turso(table = "customer")]` on the struct adds a `TABLE` constant holding the table name, but `find_by_id` is
+/// only added by `TryFromRowByIndex`, which reads the columns by position.
+///
+/// ```ignore
+/// #[derive(TryFromRowByName)]
+/// struct CustomerName {
+///     id: i64,
+///     #[turso(rename = "name")]
+///     customer_name: String,
+/// }
+///
+/// let mut statement = conn.prepare("SELECT name, id FROM customer;").await?;
+/// let column_indices = ColumnIndices::new(statement.columns());
+/// ```
+#[proc_macro_derive(TryFromRowByName, attributes(turso))]
+pub fn try_from_row_by_name_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    impl_try_from_row(ast, &[RowMapping::ByName])
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives both `turso_mappers::TryFromRowByIndex` and `turso_mappers::TryFromRowByName` from one set of field
+/// attributes, as deriving each of them would. Both mappings convert each column with the same generated function,
+/// so they only differ in how the column is found.
+///
+/// ```ignore
+/// #[derive(TryFromRow)]
+/// struct Customer {
+///     id: i64,
+///     #[turso(rename = "name")]
+///     customer_name: String,
+/// }
+/// ```
+#[proc_macro_derive(TryFromRow, attributes(turso))]
+pub fn try_from_row_by_index_and_name_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    impl_try_from_row(ast, &[RowMapping::ByIndex, RowMapping::ByName])
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `turso_mappers::TryFromValue` for a fieldless enum stored as TEXT, so it can be used as a field type with `TryFromRowByIndex`.
+///
+/// Variants map from their name unless renamed with `#[turso(rename = "...")]` on the variant or
+/// `#[turso(rename_all = "...")]` on the enum, which accepts the same case conventions as serde.
+///
+/// Enums with an integer `#[repr]`, explicit discriminants or `#[turso(value = N)]` on their variants
+/// are stored as INTEGER instead, matching each variant's value or discriminant.
+///
+/// A single variant can be marked `#[turso(other)]` to receive any unrecognized value, either as a
+/// unit variant or as a variant holding the unrecognized value as a `String`.
+///
+/// Enums also get a `ToValue` impl writing each variant back as its stored value, so they can be bound as query
+/// parameters. The only `#[turso(other)]` variant this works with is one holding the text of an enum stored as TEXT.
+///
+/// It can also be derived for a single field tuple struct, such as `struct CustomerId(i64)`, which is
+/// mapped from the same values as a field of the wrapped type.
+///
+/// ```ignore
+/// #[derive(TryFromValue)]
+/// #[turso(rename_all = "snake_case")]
+/// enum OrderStatus {
+///     Pending,
+///     Shipped,
+///     #[turso(rename = "canceled")]
+///     Cancelled,
+/// }
+/// ```
+#[proc_macro_derive(TryFromValue, attributes(turso))]
+pub fn try_from_value_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    try_from_value::impl_try_from_value(ast).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `turso_mappers::ToParams`, binding the struct fields as query parameters in declaration order.
+///
+/// `to_params` returns the values for positional parameters, and `to_named_params` pairs each value with the
+/// parameter name `:field_name`, using the column name given by `#[turso(rename = "...")]` or
+/// `#[turso(rename_all = "...")]` when set. Fields marked `#[turso(skip)]` are left out, and `Option` fields
+/// are bound as NULL when `None`. Any field type implementing `turso_mappers::ToValue` is supported.
+///
+/// ```ignore
+/// #[derive(ToParams)]
+/// struct NewCustomer {
+///     name: String,
+///     value: f64,
+///     description: Option<String>,
+/// }
+///
+/// conn.execute("INSERT INTO customer (name, value, description) VALUES (?1, ?2, ?3);", customer.to_params())
+///     .await?;
+/// conn.execute("INSERT INTO customer (name, value, description) VALUES (:name, :value, :description);", customer.to_named_params())
+///     .await?;
+/// ```
+#[proc_macro_derive(ToParams, attributes(turso))]
+pub fn to_params_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    to_params::impl_to_params(ast).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `turso_mappers::Insert` for a struct stored in the table set with `#[turso(table = "...")]`.
+///
+/// `insert_sql` returns an INSERT statement naming the column of each field, taken from `#[turso(rename = "...")]`
+/// or `#[turso(rename_all = "...")]` when set, with a positional parameter for each, and `insert_params` binds the
+/// field values to them, with `None` bound as NULL. Fields marked `#[turso(skip)]` are left out, as are columns the
+/// database fills in, such as an INTEGER PRIMARY KEY, marked `#[turso(auto)]`.
+///
+/// ```ignore
+/// #[derive(Insert)]
+/// #[turso(table = "customer")]
+/// struct Customer {
+///     #[turso(primary_key, auto)]
+///     id: i64,
+///     name: String,
+///     description: Option<String>,
+/// }
+///
+/// // INSERT INTO customer (name, description) VALUES (?, ?)
+/// let id = Customer::insert(&conn, &customer).await?;
+/// ```
+#[proc_macro_derive(Insert, attributes(turso))]
+pub fn insert_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    statements::impl_insert(ast).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `turso_mappers::Update` for a struct stored in the table set with `#[turso(table = "...")]`, with exactly
+/// one field marked `#[turso(primary_key)]`.
+///
+/// `update_sql` returns an UPDATE statement setting the column of every other field, named as with `Insert`, for the
+/// row with the struct's primary key, and `update_params` binds the field values followed by the key. Fields marked
+/// `#[turso(skip)]` are not updated.
+///
+/// ```ignore
+/// #[derive(Update)]
+/// #[turso(table = "customer")]
+/// struct Customer {
+///     #[turso(primary_key)]
+///     id: i64,
+///     name: String,
+///     description: Option<String>,
+/// }
+///
+/// // UPDATE customer SET name = ?, description = ? WHERE id = ?
+/// let rows_affected = customer.update(&conn).await?;
+/// ```
+#[proc_macro_derive(Update, attributes(turso))]
+pub fn update_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    statements::impl_update(ast).unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,117 @@
+use heck::{ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use syn::LitStr;
+
+// Case conversions accepted by #[turso(rename_all = "...")], using the same names as serde. Names are split into words
+// on underscores, hyphens and case changes, so both snake_case field names and PascalCase variant names convert.
+pub(crate) enum RenameRule {
+    Lowercase,
+    Uppercase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl RenameRule {
+    pub(crate) fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "lowercase" => Ok(RenameRule::Lowercase),
+            "UPPERCASE" => Ok(RenameRule::Uppercase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebabCase),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "unsupported rename_all rule, expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\" or \"SCREAMING-KEBAB-CASE\"",
+            )),
+        }
+    }
+
+    pub(crate) fn apply(&self, name: &str) -> String {
+        match self {
+            RenameRule::Lowercase => name.to_lowercase(),
+            RenameRule::Uppercase => name.to_uppercase(),
+            RenameRule::PascalCase => name.to_upper_camel_case(),
+            RenameRule::CamelCase => name.to_lower_camel_case(),
+            RenameRule::SnakeCase => name.to_snake_case(),
+            RenameRule::ScreamingSnakeCase => name.to_shouty_snake_case(),
+            RenameRule::KebabCase => name.to_kebab_case(),
+            RenameRule::ScreamingKebabCase => name.to_shouty_kebab_case(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    fn rule(name: &str) -> RenameRule {
+        match RenameRule::from_lit(&syn::LitStr::new(name, proc_macro2::Span::call_site())) {
+            Ok(rule) => rule,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn converts_field_names() {
+        let cases = [
+            ("lowercase", "user_id"),
+            ("UPPERCASE", "USER_ID"),
+            ("PascalCase", "UserId"),
+            ("camelCase", "userId"),
+            ("snake_case", "user_id"),
+            ("SCREAMING_SNAKE_CASE", "USER_ID"),
+            ("kebab-case", "user-id"),
+            ("SCREAMING-KEBAB-CASE", "USER-ID"),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(rule(name).apply("user_id"), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn converts_variant_names() {
+        let cases = [
+            ("lowercase", "inprogress"),
+            ("UPPERCASE", "INPROGRESS"),
+            ("PascalCase", "InProgress"),
+            ("camelCase", "inProgress"),
+            ("snake_case", "in_progress"),
+            ("SCREAMING_SNAKE_CASE", "IN_PROGRESS"),
+            ("kebab-case", "in-progress"),
+            ("SCREAMING-KEBAB-CASE", "IN-PROGRESS"),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(rule(name).apply("InProgress"), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn keeps_acronyms_and_digits_in_field_names() {
+        assert_eq!(rule("camelCase").apply("html_url"), "htmlUrl");
+        assert_eq!(rule("PascalCase").apply("html_url"), "HtmlUrl");
+        assert_eq!(rule("camelCase").apply("address_line_2"), "addressLine2");
+        assert_eq!(rule("SCREAMING_SNAKE_CASE").apply("address_line_2"), "ADDRESS_LINE_2");
+        assert_eq!(rule("kebab-case").apply("sha256_digest"), "sha256-digest");
+    }
+
+    #[test]
+    fn splits_acronyms_in_variant_names() {
+        assert_eq!(rule("snake_case").apply("HTTPServer"), "http_server");
+        assert_eq!(rule("camelCase").apply("HTTPServer"), "httpServer");
+        assert_eq!(rule("kebab-case").apply("IOError"), "io-error");
+    }
+
+    #[test]
+    fn rejects_unknown_rules() {
+        let lit = syn::LitStr::new("Title Case", proc_macro2::Span::call_site());
+        assert!(RenameRule::from_lit(&lit).is_err());
+    }
+}
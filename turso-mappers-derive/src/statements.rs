@@ -0,0 +1,216 @@
+use crate::{ContainerAttrs, FieldAttrs, column_positions, field_name, named_fields};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Generics, Ident, LitStr};
+
+// The table a statement derive writes to, which must be set with #[turso(table = "...")]
+fn table(ast: &DeriveInput, container_attrs: &ContainerAttrs, derive_name: &str) -> syn::Result<LitStr> {
+    container_attrs.table.clone().ok_or_else(|| {
+        syn::Error::new(
+            ast.ident.span(),
+            format!("turso_mappers::{} requires #[turso(table = \"...\")] on the struct", derive_name),
+        )
+    })
+}
+
+// Quotes a table or column name for the generated SQL, so that names which are SQL keywords, such as `order`, still
+// parse
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+pub(crate) fn impl_insert(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&ast)?;
+    let ident = &ast.ident;
+    let fields = named_fields(&ast, "Insert")?;
+    let table = quote_ident(&table(&ast, &container_attrs, "Insert")?.value());
+
+    let mut columns = vec![];
+    let mut values = vec![];
+
+    for (idx, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::from_field(field)?;
+        if attrs.is_skipped() || attrs.auto.is_some() {
+            continue;
+        }
+
+        columns.push(quote_ident(&container_attrs.column_name(&field_name(idx, field), &attrs)));
+
+        let f_ident = &field.ident;
+        let f_type = &field.ty;
+        values.push(quote_spanned!(f_type.span()=> <#f_type as _turso_mappers::ToValue>::to_value(&self.#f_ident)));
+    }
+
+    let sql = match columns.is_empty() {
+        true => format!("INSERT INTO {} DEFAULT VALUES", table),
+        false => format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        ),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let impls = quote! {
+        impl #impl_generics _turso_mappers::Insert for #ident #ty_generics #where_clause {
+            fn insert_sql() -> &'static str {
+                #sql
+            }
+
+            fn insert_params(&self) -> ::std::vec::Vec<_turso_mappers::turso::Value> {
+                ::std::vec![#(#values,)*]
+            }
+        }
+    };
+
+    Ok(container_attrs.wrap_impls(impls))
+}
+
+pub(crate) fn impl_update(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&ast)?;
+    let ident = &ast.ident;
+    let fields = named_fields(&ast, "Update")?;
+    let table = quote_ident(&table(&ast, &container_attrs, "Update")?.value());
+
+    let mut columns = vec![];
+    let mut values = vec![];
+    let mut primary_key = None;
+
+    for (idx, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::from_field(field)?;
+        if attrs.is_skipped() {
+            if let Some(span) = attrs.primary_key {
+                return Err(syn::Error::new(span, "the primary key field cannot be skipped"));
+            }
+            continue;
+        }
+
+        let column = quote_ident(&container_attrs.column_name(&field_name(idx, field), &attrs));
+        let f_ident = &field.ident;
+        let f_type = &field.ty;
+        let value = quote_spanned!(f_type.span()=> <#f_type as _turso_mappers::ToValue>::to_value(&self.#f_ident));
+
+        match attrs.primary_key {
+            Some(span) if primary_key.is_some() => {
+                return Err(syn::Error::new(span, "only one field can be marked #[turso(primary_key)]"));
+            }
+            Some(_) => primary_key = Some((column, value)),
+            None => {
+                columns.push(column);
+                values.push(value);
+            }
+        }
+    }
+
+    let Some((key_column, key_value)) = primary_key else {
+        return Err(syn::Error::new(
+            ident.span(),
+            "turso_mappers::Update requires a field marked #[turso(primary_key)]",
+        ));
+    };
+    if columns.is_empty() {
+        return Err(syn::Error::new(
+            ident.span(),
+            "turso_mappers::Update requires a field to update besides the primary key",
+        ));
+    }
+
+    let assignments = columns.iter().map(|column| format!("{} = ?", column)).collect::<Vec<_>>();
+    let sql = format!("UPDATE {} SET {} WHERE {} = ?", table, assignments.join(", "), key_column);
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let impls = quote! {
+        impl #impl_generics _turso_mappers::Update for #ident #ty_generics #where_clause {
+            fn update_sql() -> &'static str {
+                #sql
+            }
+
+            fn update_params(&self) -> ::std::vec::Vec<_turso_mappers::turso::Value> {
+                ::std::vec![#(#values,)* #key_value]
+            }
+        }
+    };
+
+    Ok(container_attrs.wrap_impls(impls))
+}
+
+// The TABLE constant for a struct with #[turso(table = "...")], and a find_by_id function when the struct is mapped by
+// index and a field is marked as the primary key
+pub(crate) fn table_metadata(
+    ident: &Ident,
+    generics: &Generics,
+    table: &LitStr,
+    fields: &syn::Fields,
+    field_attrs: &[FieldAttrs],
+    container_attrs: &ContainerAttrs,
+    find_by_id: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if !find_by_id {
+        return Ok(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                pub const TABLE: &'static str = #table;
+            }
+        });
+    }
+
+    let mut primary_keys = fields.iter().zip(field_attrs).enumerate().filter(|(_, (_, attrs))| attrs.primary_key.is_some());
+    let Some((key_idx, (key_field, key_attrs))) = primary_keys.next() else {
+        return Ok(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                pub const TABLE: &'static str = #table;
+            }
+        });
+    };
+    if let Some((_, (_, attrs))) = primary_keys.next() {
+        let span = attrs.primary_key.expect("only primary key fields are left");
+        return Err(syn::Error::new(span, "only one field can be marked #[turso(primary_key)]"));
+    }
+    if field_attrs.iter().any(|attrs| attrs.flatten.is_some()) {
+        return Err(syn::Error::new(
+            key_attrs.primary_key.expect("the primary key field is marked"),
+            "#[turso(primary_key)] cannot be used in a struct with flattened fields, whose columns find_by_id cannot list",
+        ));
+    }
+    if key_attrs.is_skipped() {
+        return Err(syn::Error::new(
+            key_attrs.primary_key.expect("the primary key field is marked"),
+            "the primary key field cannot be skipped",
+        ));
+    }
+
+    // Each column is selected at the position it is read from, with NULL for a position no field reads
+    let positions = column_positions(field_attrs)?;
+    let mut columns = vec!["NULL".to_string(); positions.iter().flatten().map(|position| position + 1).max().unwrap_or(0)];
+    for (idx, (field, position)) in fields.iter().zip(positions).enumerate() {
+        if let Some(position) = position {
+            columns[position] = quote_ident(&container_attrs.column_name(&field_name(idx, field), &field_attrs[idx]));
+        }
+    }
+
+    let key_column = quote_ident(&container_attrs.column_name(&field_name(key_idx, key_field), key_attrs));
+    let sql = format!("SELECT {} FROM {} WHERE {} = ?", columns.join(", "), quote_ident(&table.value()), key_column);
+    let key_type = &key_field.ty;
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub const TABLE: &'static str = #table;
+
+            /// Finds the row with the given primary key, or returns None if there is no such row.
+            pub async fn find_by_id(conn: &_turso_mappers::turso::Connection, id: &#key_type) -> _turso_mappers::TursoMapperResult<::core::option::Option<Self>>
+            where
+                Self: _turso_mappers::TryFromRowByIndex,
+            {
+                let mut rows = conn.query(#sql, ::std::vec![<#key_type as _turso_mappers::ToValue>::to_value(id)]).await?;
+                let Some(row) = rows.next().await? else {
+                    return Ok(None);
+                };
+                if rows.next().await?.is_some() {
+                    return Err(_turso_mappers::TursoMapperError::TooManyRows(#sql.to_string()));
+                }
+                <Self as _turso_mappers::TryFromRowByIndex>::try_from_row_by_index(row).map(Some)
+            }
+        }
+    })
+}
@@ -0,0 +1,145 @@
+use crate::try_from_value::VariantAttrs;
+use crate::{ContainerAttrs, FieldAttrs, FieldsMapping, RowMapping, collect_results, fields_mapping, row_fn_prefix};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DataEnum, Ident, LitStr};
+
+// Maps a row to the variant of an enum named by its tag column, reading the fields of the variant from the rest of
+// the row. Mapped by index, the tag is the first column and the fields of every variant start after it.
+pub(crate) fn tagged_enum_mapping(
+    ident: &Ident,
+    data: &DataEnum,
+    container_attrs: &ContainerAttrs,
+    row_mappings: &[RowMapping],
+    derive_name: &str,
+) -> syn::Result<FieldsMapping> {
+    let Some(tag) = &container_attrs.tag else {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "turso_mappers::{} on an enum requires #[turso(tag = \"...\")] naming the column holding the variant",
+                derive_name
+            ),
+        ));
+    };
+
+    // rename_all converts the variant names, so the fields of each variant keep their own names
+    let field_container_attrs = ContainerAttrs {
+        strict: container_attrs.strict,
+        lenient: container_attrs.lenient,
+        parse_text: container_attrs.parse_text,
+        coerce: container_attrs.coerce,
+        ..ContainerAttrs::default()
+    };
+
+    let mut names: Vec<String> = vec![];
+    let mut conversion_fns = vec![];
+    let mut arms = vec![vec![]; row_mappings.len()];
+    let mut column_counts = vec![];
+    let mut other = None;
+
+    for (v_idx, variant) in data.variants.iter().enumerate() {
+        let attrs = VariantAttrs::from_variant(variant)?;
+        let v_ident = &variant.ident;
+
+        if let Some(value) = &attrs.value {
+            return Err(syn::Error::new(value.span(), "#[turso(value = N)] is not supported on enums mapped from a row"));
+        }
+
+        if let Some(other_span) = attrs.other {
+            if other.is_some() {
+                return Err(syn::Error::new(other_span, "only one variant can be marked #[turso(other)]"));
+            }
+            if attrs.rename.is_some() || !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new(
+                    other_span,
+                    "the #[turso(other)] variant of an enum mapped from a row must be a unit variant without a rename",
+                ));
+            }
+            other = Some(v_ident);
+            continue;
+        }
+
+        if matches!(variant.fields, syn::Fields::Unnamed(_)) {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!("turso_mappers::{} only supports enum variants with named fields or no fields", derive_name),
+            ));
+        }
+
+        let name = match (&attrs.rename, &container_attrs.rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(rule)) => rule.apply(&v_ident.to_string()),
+            (None, None) => v_ident.to_string(),
+        };
+        if names.contains(&name) {
+            let span = attrs.rename.as_ref().map_or(variant.span(), LitStr::span);
+            return Err(syn::Error::new(span, format!("duplicate tag {:?} for {}", name, ident)));
+        }
+
+        let field_attrs = collect_results(variant.fields.iter().map(FieldAttrs::from_field))?;
+        let mapping = fields_mapping(
+            quote!(Self::#v_ident),
+            &variant.fields,
+            &field_attrs,
+            &field_container_attrs,
+            row_mappings,
+            &format!("{}{}_", row_fn_prefix(row_mappings), v_idx),
+        )?;
+
+        conversion_fns.extend(mapping.conversion_fns);
+        for (arms, construction) in arms.iter_mut().zip(mapping.constructions) {
+            arms.push(quote!(#name => #construction));
+        }
+        column_counts.push(mapping.column_count);
+        names.push(name);
+    }
+
+    // Listed in the error for an unknown tag, e.g. "click", "purchase"
+    let allowed = names.iter().map(|name| format!("{:?}", name)).collect::<Vec<_>>().join(", ");
+    let unknown = match other {
+        Some(other_ident) => quote!(Self::#other_ident),
+        None => quote! {
+            return Err(_turso_mappers::TursoMapperError::ConversionError(format!("{:?} is not a valid {} tag, expected one of {}", __turso_tag, stringify!(#ident), #allowed)))
+        },
+    };
+
+    let tag_fn = quote::format_ident!("__turso_mappers_{}tag", row_fn_prefix(row_mappings));
+    conversion_fns.push(quote! {
+        fn #tag_fn(value: _turso_mappers::turso::Value) -> _turso_mappers::TursoMapperResult<::std::string::String> {
+            match value {
+                _turso_mappers::turso::Value::Text(text) => Ok(text),
+                other => Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} is not a string, got {}", #tag, _turso_mappers::convert::storage_class(&other)))),
+            }
+        }
+    });
+
+    let constructions = row_mappings
+        .iter()
+        .zip(arms)
+        .map(|(row_mapping, arms)| {
+            let read_tag = match row_mapping {
+                RowMapping::ByIndex => quote! {
+                    let __turso_tag = Self::#tag_fn(_turso_mappers::convert::get_value(__turso_row, __turso_offset)?)?;
+                    let __turso_offset = __turso_offset + 1;
+                },
+                RowMapping::ByName => quote! {
+                    let __turso_tag = Self::#tag_fn(_turso_mappers::convert::get_value(__turso_row, __turso_column_indices.get_prefixed_index(__turso_prefix, #tag)?)?)?;
+                },
+            };
+            quote! {{
+                #read_tag
+                match __turso_tag.as_str() {
+                    #(#arms,)*
+                    _ => #unknown,
+                }
+            }}
+        })
+        .collect();
+
+    Ok(FieldsMapping {
+        conversion_fns,
+        constructions,
+        column_count: quote!(1 + _turso_mappers::convert::max_column_count(&[#(#column_counts),*])),
+    })
+}
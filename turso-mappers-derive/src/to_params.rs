@@ -0,0 +1,44 @@
+use crate::{ContainerAttrs, FieldAttrs, field_name, named_fields};
+use quote::{quote, quote_spanned};
+use syn::DeriveInput;
+use syn::spanned::Spanned;
+
+pub(crate) fn impl_to_params(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&ast)?;
+    let ident = &ast.ident;
+
+    let fields = named_fields(&ast, "ToParams")?;
+
+    let mut values = vec![];
+    let mut names = vec![];
+
+    for (idx, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::from_field(field)?;
+        if attrs.is_skipped() {
+            continue;
+        }
+
+        // Named parameters use the column name, so the same struct can be read back with TryFromRowByName
+        let column_name = container_attrs.column_name(&field_name(idx, field), &attrs);
+        names.push(format!(":{}", column_name));
+
+        let f_ident = &field.ident;
+        let f_type = &field.ty;
+        values.push(quote_spanned!(f_type.span()=> <#f_type as _turso_mappers::ToValue>::to_value(&self.#f_ident)));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let impls = quote! {
+        impl #impl_generics _turso_mappers::ToParams for #ident #ty_generics #where_clause {
+            fn to_params(&self) -> ::std::vec::Vec<_turso_mappers::turso::Value> {
+                ::std::vec![#(#values,)*]
+            }
+
+            fn to_named_params(&self) -> ::std::vec::Vec<(::std::string::String, _turso_mappers::turso::Value)> {
+                ::std::vec![#((::std::string::String::from(#names), #values),)*]
+            }
+        }
+    };
+
+    Ok(container_attrs.wrap_impls(impls))
+}
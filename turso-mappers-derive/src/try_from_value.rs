@@ -0,0 +1,336 @@
+use crate::{
+    ContainerAttrs, FieldAttrs, data_kind_error, field_value_mapping, get_option_inner_type, is_named, strict_value_match, type_to_string,
+    unsupported_type_error,
+};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Expr, Field, Ident, LitStr, Variant};
+
+// Options set on an enum variant with #[turso(...)]
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    pub(crate) rename: Option<LitStr>,
+    // The INTEGER this variant is stored as, overriding its discriminant
+    pub(crate) value: Option<Expr>,
+    // Absorbs any value not matching another variant
+    pub(crate) other: Option<proc_macro2::Span>,
+}
+
+impl VariantAttrs {
+    pub(crate) fn from_variant(variant: &Variant) -> syn::Result<Self> {
+        let mut attrs = VariantAttrs::default();
+
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("turso")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    attrs.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("value") {
+                    attrs.value = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("other") {
+                    attrs.other = Some(meta.path.span());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported turso attribute"))
+                }
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
+
+pub(crate) fn impl_try_from_value(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_input(&ast)?;
+    let impls = enum_or_newtype(&ast, &container_attrs)?;
+    Ok(container_attrs.wrap_impls(impls))
+}
+
+fn enum_or_newtype(ast: &DeriveInput, container_attrs: &ContainerAttrs) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &ast.ident;
+    let data = match &ast.data {
+        syn::Data::Enum(data) => data,
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
+            ..
+        }) if fields.unnamed.len() == 1 => return newtype(ident, &ast.generics, &fields.unnamed[0], container_attrs),
+        _ => {
+            return Err(data_kind_error(
+                ast,
+                "turso_mappers::TryFromValue only supports fieldless enums and single field tuple structs",
+            ));
+        }
+    };
+    let mut variants = vec![];
+    let mut other: Option<OtherVariant> = None;
+
+    for variant in &data.variants {
+        let attrs = VariantAttrs::from_variant(variant)?;
+
+        if let Some(other_span) = attrs.other {
+            if other.is_some() {
+                return Err(syn::Error::new(other_span, "only one variant can be marked #[turso(other)]"));
+            }
+            if attrs.rename.is_some() || attrs.value.is_some() {
+                return Err(syn::Error::new(other_span, "the #[turso(other)] variant cannot also have a rename or value"));
+            }
+            other = Some(OtherVariant {
+                ident: &variant.ident,
+                keeps_value: match &variant.fields {
+                    syn::Fields::Unit => false,
+                    syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 && is_named(&fields.unnamed[0].ty, "String") => true,
+                    _ => {
+                        return Err(syn::Error::new(
+                            variant.span(),
+                            "the #[turso(other)] variant must be a unit variant or hold a single String",
+                        ));
+                    }
+                },
+            });
+            continue;
+        }
+
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "turso_mappers::TryFromValue only supports fieldless enum variants",
+            ));
+        }
+
+        variants.push((variant, attrs));
+    }
+
+    // Enums with an integer repr, explicit discriminants or #[turso(value = N)] are stored as INTEGER, all others as TEXT
+    let stored_as_integer = has_integer_repr(ast)? || variants.iter().any(|(variant, attrs)| variant.discriminant.is_some() || attrs.value.is_some());
+
+    if stored_as_integer {
+        integer_enum(ident, container_attrs, &variants, other)
+    } else {
+        text_enum(ident, container_attrs, &variants, other)
+    }
+}
+
+// The variant marked #[turso(other)], which either holds the unrecognized text or nothing
+struct OtherVariant<'a> {
+    ident: &'a Ident,
+    keeps_value: bool,
+}
+
+// A newtype such as `struct CustomerId(i64)` is converted with the same mapping as a field of the wrapped type
+fn newtype(ident: &Ident, generics: &syn::Generics, field: &Field, container_attrs: &ContainerAttrs) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = FieldAttrs::from_field(field)?;
+
+    if get_option_inner_type(&field.ty).is_some() {
+        return Err(syn::Error::new(
+            field.ty.span(),
+            "turso_mappers::TryFromValue does not support wrapping an Option",
+        ));
+    }
+
+    let f_name = ident.to_string();
+    let Some(mapping) = field_value_mapping(&field.ty, &f_name, &attrs, attrs.numeric_coercion(container_attrs))? else {
+        return Err(unsupported_type_error(&field.ty, type_to_string(&field.ty)));
+    };
+    let value = strict_value_match(quote!(value), &f_name, mapping);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics _turso_mappers::TryFromValue for #ident #ty_generics #where_clause {
+            fn try_from_value(value: _turso_mappers::turso::Value) -> _turso_mappers::TursoMapperResult<Self> {
+                Ok(Self(#value))
+            }
+        }
+    })
+}
+
+// Checks for #[repr(i64)] and the other integer reprs
+fn has_integer_repr(ast: &DeriveInput) -> syn::Result<bool> {
+    let mut integer_repr = false;
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            const INTEGER_REPRS: [&str; 12] = ["i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize"];
+            integer_repr |= INTEGER_REPRS.iter().any(|repr| meta.path.is_ident(repr));
+            // Skip any arguments, e.g. align(8)
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(integer_repr)
+}
+
+fn text_enum(
+    ident: &Ident,
+    container_attrs: &ContainerAttrs,
+    variants: &[(&Variant, VariantAttrs)],
+    other: Option<OtherVariant>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut names: Vec<String> = vec![];
+
+    for (variant, attrs) in variants {
+        let name = match (&attrs.rename, &container_attrs.rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(rule)) => rule.apply(&variant.ident.to_string()),
+            (None, None) => variant.ident.to_string(),
+        };
+
+        if names.contains(&name) {
+            let span = attrs.rename.as_ref().map_or(variant.span(), LitStr::span);
+            return Err(syn::Error::new(span, format!("duplicate value {:?} for {}", name, ident)));
+        }
+
+        names.push(name);
+    }
+
+    let variant_idents = variants.iter().map(|(variant, _)| &variant.ident).collect::<Vec<_>>();
+
+    // Listed in the error for an unknown value, e.g. "pending", "shipped"
+    let allowed = names.iter().map(|name| format!("{:?}", name)).collect::<Vec<_>>().join(", ");
+    let to_value = match &other {
+        Some(OtherVariant {
+            ident: other_ident,
+            keeps_value: true,
+        }) => to_value_impl(
+            ident,
+            quote! {
+                #(Self::#variant_idents => _turso_mappers::turso::Value::Text(#names.to_string()),)*
+                Self::#other_ident(text) => _turso_mappers::turso::Value::Text(text.clone()),
+            },
+        ),
+        // The unrecognized text is dropped, so there is nothing to write back
+        Some(_) => quote!(),
+        None => to_value_impl(
+            ident,
+            quote!(#(Self::#variant_idents => _turso_mappers::turso::Value::Text(#names.to_string()),)*),
+        ),
+    };
+    let unknown = match other {
+        Some(OtherVariant {
+            ident: other_ident,
+            keeps_value: true,
+        }) => quote!(Ok(Self::#other_ident(text))),
+        Some(OtherVariant {
+            ident: other_ident,
+            keeps_value: false,
+        }) => quote!(Ok(Self::#other_ident)),
+        None => quote! {
+            Err(_turso_mappers::TursoMapperError::ConversionError(format!("{:?} is not a valid {}, expected one of {}", text, stringify!(#ident), #allowed)))
+        },
+    };
+
+    Ok(quote! {
+        impl _turso_mappers::TryFromValue for #ident {
+            fn try_from_value(value: _turso_mappers::turso::Value) -> _turso_mappers::TursoMapperResult<Self> {
+                match value {
+                    _turso_mappers::turso::Value::Text(text) => match text.as_str() {
+                        #(#names => Ok(Self::#variant_idents),)*
+                        _ => #unknown,
+                    },
+                    other => Err(_turso_mappers::TursoMapperError::ConversionError(format!("expected a string to map to {}, got {}", stringify!(#ident), _turso_mappers::convert::storage_class(&other)))),
+                }
+            }
+        }
+
+        #to_value
+    })
+}
+
+fn integer_enum(
+    ident: &Ident,
+    container_attrs: &ContainerAttrs,
+    variants: &[(&Variant, VariantAttrs)],
+    other: Option<OtherVariant>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if container_attrs.rename_all.is_some() {
+        return Err(syn::Error::new(ident.span(), "rename_all is not supported on enums stored as integers"));
+    }
+
+    let mut values = vec![];
+    let mut seen_values: Vec<String> = vec![];
+
+    for (variant, attrs) in variants {
+        if let Some(rename) = &attrs.rename {
+            return Err(syn::Error::new(rename.span(), "rename is not supported on enums stored as integers"));
+        }
+
+        let variant_ident = &variant.ident;
+        values.push(match &attrs.value {
+            Some(value) => {
+                let value_text = quote!(#value).to_string();
+                if seen_values.contains(&value_text) {
+                    return Err(syn::Error::new(value.span(), format!("duplicate value {} for {}", value_text, ident)));
+                }
+                seen_values.push(value_text);
+                quote!((#value))
+            }
+            // Casting to the discriminant is only possible while every variant is fieldless
+            None if other.as_ref().is_some_and(|other| other.keeps_value) => {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "every variant needs #[turso(value = N)] when the #[turso(other)] variant holds a String",
+                ));
+            }
+            // Fall back to the variant's own discriminant, explicit or implicit
+            None => quote!((Self::#variant_ident as i64)),
+        });
+    }
+
+    let variant_idents = variants.iter().map(|(variant, _)| &variant.ident).collect::<Vec<_>>();
+    let variant_count = variants.len();
+    // The other variant only keeps the unrecognized integer as text, so only enums without one are written back
+    let to_value = match other {
+        Some(_) => quote!(),
+        None => to_value_impl(ident, quote!(#(Self::#variant_idents => _turso_mappers::turso::Value::Integer(#values),)*)),
+    };
+    let unknown = match other {
+        Some(OtherVariant {
+            ident: other_ident,
+            keeps_value: true,
+        }) => quote!(Ok(Self::#other_ident(value.to_string()))),
+        Some(OtherVariant {
+            ident: other_ident,
+            keeps_value: false,
+        }) => quote!(Ok(Self::#other_ident)),
+        None => quote! {
+            {
+                let allowed: [i64; #variant_count] = [#(#values),*];
+                let allowed = allowed.iter().map(i64::to_string).collect::<Vec<_>>().join(", ");
+                Err(_turso_mappers::TursoMapperError::ConversionError(format!("{} is not a valid {}, expected one of {}", value, stringify!(#ident), allowed)))
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl _turso_mappers::TryFromValue for #ident {
+            fn try_from_value(value: _turso_mappers::turso::Value) -> _turso_mappers::TursoMapperResult<Self> {
+                match value {
+                    _turso_mappers::turso::Value::Integer(value) => match value {
+                        #(value if value == #values => Ok(Self::#variant_idents),)*
+                        value => #unknown,
+                    },
+                    other => Err(_turso_mappers::TursoMapperError::ConversionError(format!("expected an integer to map to {}, got {}", stringify!(#ident), _turso_mappers::convert::storage_class(&other)))),
+                }
+            }
+        }
+
+        #to_value
+    })
+}
+
+// Writes each variant back as the value it is read from, so the enum can also be bound as a query parameter
+fn to_value_impl(ident: &Ident, arms: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        impl _turso_mappers::ToValue for #ident {
+            fn to_value(&self) -> _turso_mappers::turso::Value {
+                match self {
+                    #arms
+                }
+            }
+        }
+    }
+}